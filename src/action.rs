@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use strum::Display;
 
+use crate::{bible::search, bookmarks::Bookmark, highlights::Highlight, notes::Note, tags::Tag};
+
 #[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
 pub enum Action {
     Tick,
@@ -12,7 +14,10 @@ pub enum Action {
     ClearScreen,
     Error(String),
     Help,
-    OpenPassage { book: String, chapter: u16 },
+    OpenPassage {
+        book: String,
+        chapter: u16,
+    },
     Scroll(i16),
     ChangeFocus,
     Filter,
@@ -20,4 +25,75 @@ pub enum Action {
     MoveCol(i32),
     Yank,
     ToggleVisual,
+    PickTranslation,
+    SwitchTranslation(String),
+    ToggleParallel(String),
+    FocusColumn(i32),
+    ToggleCompare,
+    ToggleContinuous,
+    ToggleProse,
+    NextChapter,
+    PrevChapter,
+    NextBook,
+    PrevBook,
+    ListDifferences,
+    Goto,
+    Search,
+    /// Open the concordance of a word, or its prompt when the word is empty.
+    Concordance(String),
+    /// Mark what a search matched wherever the reader shows it; an empty
+    /// query clears the marks.
+    MarkMatches {
+        query: String,
+        mode: search::Mode,
+    },
+    /// Put the reader's cursor on a verse of the open chapter, highlighting
+    /// up to `end` when given.
+    SelectVerse {
+        verse: u16,
+        end: Option<u16>,
+    },
+    /// Bookmark the verse at the reader's cursor, or remove its bookmark.
+    ToggleBookmark,
+    /// Add a bookmark, or replace the one on the same verse.
+    SaveBookmark(Bookmark),
+    DeleteBookmark {
+        book: String,
+        chapter: u16,
+        verse: u16,
+    },
+    Bookmarks,
+    /// Colour the reader's selection, or the verse at its cursor, with a
+    /// `highlight_` style; an empty colour erases the highlights there.
+    HighlightSelection(String),
+    SaveHighlight(Highlight),
+    DeleteHighlight(Highlight),
+    Highlights,
+    /// Write a note on the reader's selection, or open the one there.
+    EditNote,
+    /// Open a note in the editor.
+    OpenNote(Note),
+    /// Save a note; one left empty is deleted.
+    SaveNote(Note),
+    DeleteNote(Note),
+    /// Hand a note to `$EDITOR`, then save what it leaves.
+    EditNoteExternally(Note),
+    ToggleNotePane,
+    Notes,
+    /// Name a tag to put on the reader's selection, or take off it.
+    TagSelection,
+    SaveTag(Tag),
+    DeleteTag(Tag),
+    Tags,
+    /// Start following a reading plan from today.
+    StartPlan(String),
+    StopPlan(String),
+    /// Mark a day of a plan as read, or not.
+    MarkDay {
+        plan: String,
+        day: usize,
+        done: bool,
+    },
+    Plans,
+    ClosePopup,
 }
@@ -1,4 +1,9 @@
-use color_eyre::Result;
+use std::sync::Arc;
+
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
 use crossterm::event::KeyEvent;
 use ratatui::prelude::{Constraint, Rect};
 use serde::{Deserialize, Serialize};
@@ -8,11 +13,25 @@ use tracing::{debug, info};
 use crate::{
     action::Action,
     bible::Bible,
-    components::{Component, fps::FpsCounter, index::Index, reader::Reader},
+    bookmarks::Bookmarks,
+    components::{
+        Component, bookmarks::BookmarkList, concordance::Concordance, differences::Differences,
+        editor::NoteEditor, fps::FpsCounter, goto::Goto, highlights::HighlightList, index::Index,
+        notes::NoteList, picker::TranslationPicker, plans::PlanView, reader::Reader,
+        search::Search, tags::TagBrowser,
+    },
     config::Config,
+    highlights::Highlights,
+    library::{Library, Translation},
+    notes::{self, Note, Notes},
+    plans::{Date, Plans},
+    tags::Tags,
     tui::{Event, Tui},
 };
 
+/// Most translations the parallel view shows at once, the main one included.
+const MAX_COLUMNS: usize = 4;
+
 pub struct App {
     config: Config,
     tick_rate: f64,
@@ -25,7 +44,21 @@ pub struct App {
     action_tx: mpsc::UnboundedSender<Action>,
     action_rx: mpsc::UnboundedReceiver<Action>,
     focus: Focus,
+    /// Where focus goes back to when a popup closes.
+    last_focus: Focus,
     filtering_mode: bool,
+    library: Library,
+    translation: Translation,
+    bible: Arc<Bible>,
+    /// Translations shown side by side with the main one.
+    parallel: Vec<(Translation, Arc<Bible>)>,
+    bookmarks: Bookmarks,
+    highlights: Highlights,
+    notes: Notes,
+    tags: Tags,
+    plans: Plans,
+    /// Note to hand to `$EDITOR` once the current actions are done.
+    external_note: Option<Note>,
 }
 
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -38,29 +71,90 @@ pub enum Mode {
 pub enum Focus {
     Index,
     Reader,
+    Picker,
+    Differences,
+    Goto,
+    Search,
+    Concordance,
+    Bookmarks,
+    Highlights,
+    NoteEditor,
+    Notes,
+    Tags,
+    Plans,
+}
+
+impl Focus {
+    /// Popups are drawn over the panes and handle their own keys.
+    pub fn is_popup(self) -> bool {
+        matches!(
+            self,
+            Focus::Picker
+                | Focus::Differences
+                | Focus::Goto
+                | Focus::Search
+                | Focus::Concordance
+                | Focus::Bookmarks
+                | Focus::Highlights
+                | Focus::NoteEditor
+                | Focus::Notes
+                | Focus::Tags
+                | Focus::Plans
+        )
+    }
 }
 
 impl App {
-    pub fn new(tick_rate: f64, frame_rate: f64) -> Result<Self> {
-        let bible = Bible::load_tsv("books/kjv.tsv")?;
+    pub fn new(tick_rate: f64, frame_rate: f64, translation: Option<String>) -> Result<Self> {
+        let config = Config::new()?;
+        let library = Library::new(&config);
+        let translation = library.resolve(translation.as_deref())?.clone();
+        let bible = load(&translation)?;
+        let bookmarks = Bookmarks::load()?;
+        let highlights = Highlights::load()?;
+        let notes = Notes::load()?;
+        let tags = Tags::load()?;
+        let plans = Plans::load()?;
         let (action_tx, action_rx) = mpsc::unbounded_channel();
         Ok(Self {
             tick_rate,
             frame_rate,
             components: vec![
-                Box::new(Index::new(bible.clone())),
-                Box::new(Reader::new(bible.clone())),
+                Box::new(Index::new()),
+                Box::new(Reader::new()),
                 Box::new(FpsCounter::default()),
+                Box::new(TranslationPicker::new(&library)),
+                Box::new(Differences::new()),
+                Box::new(Goto::new()),
+                Box::new(Search::new()),
+                Box::new(Concordance::new()),
+                Box::new(BookmarkList::new()),
+                Box::new(HighlightList::new()),
+                Box::new(NoteList::new()),
+                Box::new(NoteEditor::new()),
+                Box::new(TagBrowser::new()),
+                Box::new(PlanView::new()),
             ],
             should_quit: false,
             should_suspend: false,
-            config: Config::new()?,
+            config,
             mode: Mode::Home,
             last_tick_key_events: Vec::new(),
             action_tx,
             action_rx,
             focus: Focus::Index,
+            last_focus: Focus::Index,
             filtering_mode: false,
+            library,
+            translation,
+            bible: Arc::new(bible),
+            parallel: Vec::new(),
+            bookmarks,
+            highlights,
+            notes,
+            tags,
+            plans,
+            external_note: None,
         })
     }
 
@@ -76,14 +170,37 @@ impl App {
         for component in self.components.iter_mut() {
             component.register_config_handler(self.config.clone())?;
         }
+        for component in self.components.iter_mut() {
+            component.register_bible_handler(self.bible.clone(), &self.translation)?;
+        }
+        for component in self.components.iter_mut() {
+            component.register_bookmarks_handler(&self.bookmarks)?;
+            component.register_highlights_handler(&self.highlights)?;
+            component.register_notes_handler(&self.notes)?;
+            component.register_tags_handler(&self.tags)?;
+            component.register_plans_handler(&self.plans)?;
+        }
         for component in self.components.iter_mut() {
             component.init(tui.size()?)?;
         }
+        index_in_background(&self.bible);
 
         let action_tx = self.action_tx.clone();
         loop {
             self.handle_events(&mut tui).await?;
             self.handle_actions(&mut tui)?;
+            if let Some(note) = self.external_note.take() {
+                // Like suspending, but the editor runs in our place instead
+                // of the shell.
+                tui.exit()?;
+                let edited = notes::edit_in_editor(&note);
+                tui.resume()?;
+                action_tx.send(Action::ClearScreen)?;
+                match edited {
+                    Ok(note) => action_tx.send(Action::SaveNote(note))?,
+                    Err(e) => action_tx.send(Action::Error(format!("{e:#}")))?,
+                }
+            }
             if self.should_suspend {
                 tui.suspend()?;
                 action_tx.send(Action::Resume)?;
@@ -114,7 +231,18 @@ impl App {
         }
         for component in self.components.iter_mut() {
             let owns_focus = (self.focus == Focus::Index && component.as_any().is::<Index>())
-                || (self.focus == Focus::Reader && component.as_any().is::<Reader>());
+                || (self.focus == Focus::Reader && component.as_any().is::<Reader>())
+                || (self.focus == Focus::Picker && component.as_any().is::<TranslationPicker>())
+                || (self.focus == Focus::Differences && component.as_any().is::<Differences>())
+                || (self.focus == Focus::Goto && component.as_any().is::<Goto>())
+                || (self.focus == Focus::Search && component.as_any().is::<Search>())
+                || (self.focus == Focus::Concordance && component.as_any().is::<Concordance>())
+                || (self.focus == Focus::Bookmarks && component.as_any().is::<BookmarkList>())
+                || (self.focus == Focus::Highlights && component.as_any().is::<HighlightList>())
+                || (self.focus == Focus::NoteEditor && component.as_any().is::<NoteEditor>())
+                || (self.focus == Focus::Notes && component.as_any().is::<NoteList>())
+                || (self.focus == Focus::Tags && component.as_any().is::<TagBrowser>())
+                || (self.focus == Focus::Plans && component.as_any().is::<PlanView>());
 
             if owns_focus {
                 if let Some(action) = component.handle_events(Some(event.clone()))? {
@@ -141,6 +269,9 @@ impl App {
             }
             return Ok(());
         }
+        if self.focus.is_popup() || self.components.iter().any(|c| c.captures_input()) {
+            return Ok(());
+        }
 
         let Some(keymap) = self.config.keybindings.get(&self.mode) else {
             return Ok(());
@@ -174,11 +305,7 @@ impl App {
                 Action::Quit => self.should_quit = true,
                 Action::Suspend => self.should_suspend = true,
                 Action::Resume => self.should_suspend = false,
-                Action::Filter => {
-                    if self.focus == Focus::Index {
-                        self.filtering_mode = true;
-                    }
-                }
+                Action::Filter if self.focus == Focus::Index => self.filtering_mode = true,
                 Action::ClearScreen => tui.terminal.clear()?,
                 Action::Resize(w, h) => self.handle_resize(tui, w, h)?,
                 Action::Render => self.render(tui)?,
@@ -186,7 +313,124 @@ impl App {
                     self.focus = match self.focus {
                         Focus::Index => Focus::Reader,
                         Focus::Reader => Focus::Index,
-                        // ...
+                        popup => popup,
+                    }
+                }
+                Action::PickTranslation => self.open_popup(Focus::Picker),
+                Action::ListDifferences => self.open_popup(Focus::Differences),
+                Action::Goto => self.open_popup(Focus::Goto),
+                Action::Search => self.open_popup(Focus::Search),
+                Action::Concordance(_) => self.open_popup(Focus::Concordance),
+                Action::Bookmarks => self.open_popup(Focus::Bookmarks),
+                Action::Highlights => self.open_popup(Focus::Highlights),
+                Action::Notes => self.open_popup(Focus::Notes),
+                Action::OpenNote(_) => self.open_popup(Focus::NoteEditor),
+                Action::Tags => self.open_popup(Focus::Tags),
+                Action::Plans => self.open_popup(Focus::Plans),
+                Action::ClosePopup => self.focus = self.last_focus,
+                Action::SwitchTranslation(ref abbrev) => match self.switch_translation(abbrev) {
+                    Ok(()) => {
+                        if self.focus == Focus::Picker {
+                            self.focus = self.last_focus;
+                        }
+                    }
+                    Err(e) => self.action_tx.send(Action::Error(format!("{e:#}")))?,
+                },
+                Action::SaveBookmark(ref bookmark) => {
+                    self.bookmarks.set(bookmark.clone());
+                    let result = self.bookmarks.save();
+                    Self::publish(&self.action_tx, &mut self.components, result, |c| {
+                        c.register_bookmarks_handler(&self.bookmarks)
+                    })?;
+                }
+                Action::DeleteBookmark {
+                    ref book,
+                    chapter,
+                    verse,
+                } => {
+                    self.bookmarks.remove(book, chapter, verse);
+                    let result = self.bookmarks.save();
+                    Self::publish(&self.action_tx, &mut self.components, result, |c| {
+                        c.register_bookmarks_handler(&self.bookmarks)
+                    })?;
+                }
+                Action::SaveHighlight(ref highlight) => {
+                    self.highlights.add(highlight.clone());
+                    let result = self.highlights.save();
+                    Self::publish(&self.action_tx, &mut self.components, result, |c| {
+                        c.register_highlights_handler(&self.highlights)
+                    })?;
+                }
+                Action::DeleteHighlight(ref highlight) => {
+                    self.highlights.remove(highlight);
+                    let result = self.highlights.save();
+                    Self::publish(&self.action_tx, &mut self.components, result, |c| {
+                        c.register_highlights_handler(&self.highlights)
+                    })?;
+                }
+                Action::SaveTag(ref tag) => {
+                    self.tags.add(tag.clone());
+                    let result = self.tags.save();
+                    Self::publish(&self.action_tx, &mut self.components, result, |c| {
+                        c.register_tags_handler(&self.tags)
+                    })?;
+                }
+                Action::DeleteTag(ref tag) => {
+                    self.tags.remove(tag);
+                    let result = self.tags.save();
+                    Self::publish(&self.action_tx, &mut self.components, result, |c| {
+                        c.register_tags_handler(&self.tags)
+                    })?;
+                }
+                Action::StartPlan(ref plan) => {
+                    self.plans.start(plan, Date::today());
+                    let result = self.plans.save();
+                    Self::publish(&self.action_tx, &mut self.components, result, |c| {
+                        c.register_plans_handler(&self.plans)
+                    })?;
+                }
+                Action::StopPlan(ref plan) => {
+                    self.plans.stop(plan);
+                    let result = self.plans.save();
+                    Self::publish(&self.action_tx, &mut self.components, result, |c| {
+                        c.register_plans_handler(&self.plans)
+                    })?;
+                }
+                Action::MarkDay {
+                    ref plan,
+                    day,
+                    done,
+                } => {
+                    self.plans.mark(plan, day, done);
+                    let result = self.plans.save();
+                    Self::publish(&self.action_tx, &mut self.components, result, |c| {
+                        c.register_plans_handler(&self.plans)
+                    })?;
+                }
+                Action::SaveNote(ref note) => {
+                    let result = self.notes.save(note.clone());
+                    Self::publish(&self.action_tx, &mut self.components, result, |c| {
+                        c.register_notes_handler(&self.notes)
+                    })?;
+                    if self.focus == Focus::NoteEditor {
+                        self.focus = self.last_focus;
+                    }
+                }
+                Action::DeleteNote(ref note) => {
+                    let result = self.notes.delete(note);
+                    Self::publish(&self.action_tx, &mut self.components, result, |c| {
+                        c.register_notes_handler(&self.notes)
+                    })?;
+                }
+                Action::EditNoteExternally(ref note) => {
+                    self.external_note = Some(note.clone());
+                    if self.focus == Focus::NoteEditor {
+                        self.focus = self.last_focus;
+                    }
+                }
+                Action::ToggleParallel(ref abbrev) => {
+                    if let Err(e) = self.toggle_parallel(abbrev) {
+                        self.action_tx.send(Action::Error(format!("{e:#}")))?;
                     }
                 }
                 _ => {}
@@ -200,6 +444,63 @@ impl App {
         Ok(())
     }
 
+    fn open_popup(&mut self, popup: Focus) {
+        if !self.focus.is_popup() {
+            self.last_focus = self.focus;
+        }
+        self.focus = popup;
+    }
+
+    /// Load another translation from the library and hand it to every
+    /// component; the current one stays if loading fails.
+    fn switch_translation(&mut self, abbrev: &str) -> Result<()> {
+        let translation = self.library.resolve(Some(abbrev))?.clone();
+        if translation == self.translation {
+            return Ok(());
+        }
+        self.bible = Arc::new(load(&translation)?);
+        index_in_background(&self.bible);
+        self.translation = translation;
+        for component in self.components.iter_mut() {
+            component.register_bible_handler(self.bible.clone(), &self.translation)?;
+        }
+        Ok(())
+    }
+
+    /// Add a translation to the parallel view, or remove it if it is shown.
+    fn toggle_parallel(&mut self, abbrev: &str) -> Result<()> {
+        let translation = self.library.resolve(Some(abbrev))?.clone();
+        if let Some(i) = self.parallel.iter().position(|(t, _)| *t == translation) {
+            self.parallel.remove(i);
+        } else if self.parallel.len() + 1 >= MAX_COLUMNS {
+            return Err(eyre!(
+                "at most {MAX_COLUMNS} translations can be shown side by side"
+            ));
+        } else {
+            let bible = Arc::new(load(&translation)?);
+            self.parallel.push((translation, bible));
+        }
+        for component in self.components.iter_mut() {
+            component.register_parallel_handler(&self.parallel)?;
+        }
+        Ok(())
+    }
+
+    /// Report `result`, a failed write of the user's data, and hand the data
+    /// to every component with `register`; a change that could not be saved
+    /// is kept for this session.
+    fn publish(
+        action_tx: &mpsc::UnboundedSender<Action>,
+        components: &mut [Box<dyn Component>],
+        result: Result<()>,
+        register: impl Fn(&mut Box<dyn Component>) -> Result<()>,
+    ) -> Result<()> {
+        if let Err(e) = result {
+            action_tx.send(Action::Error(format!("{e:#}")))?;
+        }
+        components.iter_mut().try_for_each(register)
+    }
+
     fn handle_resize(&mut self, tui: &mut Tui, w: u16, h: u16) -> Result<()> {
         tui.resize(Rect::new(0, 0, w, h))?;
         self.render(tui)?;
@@ -228,3 +529,20 @@ impl App {
         Ok(())
     }
 }
+
+/// Build the search index of a freshly loaded bible off the UI thread, so the
+/// first search does not wait for it. A database-backed bible is left to
+/// build it on its first search, rather than reading every chapter now.
+fn index_in_background(bible: &Arc<Bible>) {
+    if bible.is_stored() {
+        return;
+    }
+    let bible = bible.clone();
+    tokio::task::spawn_blocking(move || {
+        bible.search_index();
+    });
+}
+
+fn load(translation: &Translation) -> Result<Bible> {
+    Bible::load(&translation.path).wrap_err_with(|| format!("cannot load {}", translation.abbrev))
+}
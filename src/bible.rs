@@ -2,26 +2,212 @@ use color_eyre::{
     Result,
     eyre::{WrapErr, eyre},
 };
+use quick_xml::events::BytesStart;
 use std::{
     fs::File,
-    io::{BufRead, BufReader},
-    path::Path,
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
 };
 
-#[derive(Clone)]
+pub mod canon;
+pub mod diff;
+mod opensong;
+mod osis;
+pub mod reference;
+pub mod search;
+mod sqlite;
+pub mod sword;
+mod usfm;
+mod usx;
+mod zefania;
+
+/// The source formats [`Bible::load`] can tell apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Tsv,
+    Osis,
+    Usfm,
+    Usx,
+    Zefania,
+    OpenSong,
+    /// A SWORD module, addressed by its `mods.d/*.conf` file.
+    Sword,
+    /// A MySword, MyBible or scrollmapper SQLite database.
+    Sqlite,
+}
+
+impl Format {
+    /// Guess the format from the beginning of a file; XML with a root
+    /// element none of the loaders know is an error.
+    pub fn detect(head: &str) -> Result<Self> {
+        if head.starts_with("SQLite format 3") {
+            return Ok(Self::Sqlite);
+        }
+        let head = head.trim_start_matches('\u{feff}').trim_start();
+        if head.starts_with('<') {
+            let root = root_element(head);
+            return match root.to_ascii_lowercase().as_str() {
+                "osis" => Ok(Self::Osis),
+                "usx" => Ok(Self::Usx),
+                "xmlbible" => Ok(Self::Zefania),
+                "bible" => Ok(Self::OpenSong),
+                _ => Err(eyre!("unrecognised XML with root element <{root}>")),
+            };
+        }
+        if head.starts_with('[') && head.contains("ModDrv=") {
+            return Ok(Self::Sword);
+        }
+        if head.starts_with("\\id ") || head.contains("\n\\c ") {
+            return Ok(Self::Usfm);
+        }
+        Ok(Self::Tsv)
+    }
+}
+
+/// Name of the first element of an XML document, skipping the prolog,
+/// comments and doctype.
+fn root_element(xml: &str) -> &str {
+    let mut rest = xml;
+    while let Some(i) = rest.find('<') {
+        rest = &rest[i + 1..];
+        if rest.starts_with(|c: char| c.is_alphabetic()) {
+            let end = rest
+                .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                .unwrap_or(rest.len());
+            // Drop a namespace prefix such as `osis:`.
+            let name = &rest[..end];
+            return name.rsplit(':').next().unwrap_or(name);
+        }
+    }
+    ""
+}
+
+#[derive(Debug, Default, Clone)]
 pub struct Verse {
     pub book: String,
     pub chapter: u16,
     pub verse: u16,
     pub text: String,
+    /// Paragraph and poetry markers from the source, in text order.
+    pub breaks: Vec<Break>,
+}
+
+/// A layout marker that starts at byte `offset` of a verse's text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Break {
+    pub offset: usize,
+    pub kind: BreakKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakKind {
+    Paragraph,
+    /// A poetic line, with its indentation level (1 for `\q1`).
+    Poetry(u8),
 }
 
-#[derive(Default, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Footnote {
+    pub book: String,
+    pub chapter: u16,
+    pub verse: u16,
+    pub caller: String,
+    pub text: String,
+}
+
+#[derive(Debug, Default, Clone)]
 pub struct Bible {
     pub verses: Vec<Verse>,
+    pub footnotes: Vec<Footnote>,
+    /// Set for database-backed bibles, whose chapters are read on demand
+    /// instead of living in `verses`.
+    store: Option<Arc<sqlite::Store>>,
+    search: OnceLock<search::Index>,
 }
 
 impl Bible {
+    /// Load a bible, picking the loader from the contents of the file. A
+    /// directory is read as a USFM project with one file per book. A file
+    /// that yields no verses is an error, as there would be nothing to read.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let bible = if path.as_ref().is_dir() {
+            Self::load_usfm_dir(&path)?
+        } else {
+            let mut head = Vec::new();
+            File::open(&path)
+                .and_then(|f| f.take(4096).read_to_end(&mut head))
+                .wrap_err_with(|| format!("cannot open {:?}", path.as_ref()))?;
+            let format = Format::detect(&String::from_utf8_lossy(&head))
+                .wrap_err_with(|| format!("cannot load {:?}", path.as_ref()))?;
+            match format {
+                Format::Osis => Self::load_osis(&path),
+                Format::Usfm => Self::load_usfm(&path),
+                Format::Usx => Self::load_usx(&path),
+                Format::Zefania => Self::load_zefania(&path),
+                Format::OpenSong => Self::load_opensong(&path),
+                Format::Sword => sword::Module::from_conf(path.as_ref())?.load(),
+                Format::Sqlite => Self::load_sqlite(&path),
+                Format::Tsv => Self::load_tsv(&path),
+            }?
+        };
+        if bible.verses.is_empty() && bible.store.is_none() {
+            return Err(eyre!("no verses found in {:?}", path.as_ref()));
+        }
+        Ok(bible)
+    }
+
+    pub fn load_osis(path: impl AsRef<Path>) -> Result<Self> {
+        osis::parse(&read(&path)?).wrap_err_with(|| format!("cannot parse {:?}", path.as_ref()))
+    }
+
+    pub fn load_usfm(path: impl AsRef<Path>) -> Result<Self> {
+        usfm::parse(&read(&path)?).wrap_err_with(|| format!("cannot parse {:?}", path.as_ref()))
+    }
+
+    /// Load every `.usfm`/`.sfm` file of `dir`, merging the books in canon
+    /// order whatever the file names are.
+    pub fn load_usfm_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        let files = usfm_files(dir.as_ref());
+        if files.is_empty() {
+            return Err(eyre!("no USFM files in {:?}", dir.as_ref()));
+        }
+        let mut books = files
+            .iter()
+            .map(Self::load_usfm)
+            .collect::<Result<Vec<_>>>()?;
+        books.sort_by_key(|b| {
+            b.verses
+                .first()
+                .map_or(usize::MAX, |v| canon::order(&v.book))
+        });
+        let mut bible = Self::default();
+        for book in books {
+            bible.verses.extend(book.verses);
+            bible.footnotes.extend(book.footnotes);
+        }
+        Ok(bible)
+    }
+
+    pub fn load_usx(path: impl AsRef<Path>) -> Result<Self> {
+        usx::parse(&read(&path)?).wrap_err_with(|| format!("cannot parse {:?}", path.as_ref()))
+    }
+
+    pub fn load_zefania(path: impl AsRef<Path>) -> Result<Self> {
+        zefania::parse(&read(&path)?).wrap_err_with(|| format!("cannot parse {:?}", path.as_ref()))
+    }
+
+    pub fn load_opensong(path: impl AsRef<Path>) -> Result<Self> {
+        opensong::parse(&read(&path)?).wrap_err_with(|| format!("cannot parse {:?}", path.as_ref()))
+    }
+
+    pub fn load_sqlite(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self {
+            store: Some(Arc::new(sqlite::Store::open(path.as_ref())?)),
+            ..Default::default()
+        })
+    }
+
     pub fn load_tsv(path: impl AsRef<Path>) -> Result<Self> {
         let file =
             File::open(&path).wrap_err_with(|| format!("cannot open {:?}", path.as_ref()))?;
@@ -49,7 +235,10 @@ impl Bible {
                 }
             }
         }
-        Ok(Self { verses: out })
+        Ok(Self {
+            verses: out,
+            ..Default::default()
+        })
     }
 
     fn make_verse(book: &str, ch: &str, vs: &str, txt: &str, ln: usize) -> Result<Verse> {
@@ -64,11 +253,15 @@ impl Bible {
                 .parse()
                 .wrap_err_with(|| format!("line {ln}: bad verse “{vs}”"))?,
             text: txt.to_owned(),
+            ..Default::default()
         })
     }
 
     pub fn chapters(&self) -> Vec<(String, u16)> {
         use itertools::Itertools;
+        if let Some(store) = &self.store {
+            return store.chapters();
+        }
         self.verses
             .iter()
             .map(|v| (v.book.clone(), v.chapter))
@@ -77,9 +270,226 @@ impl Bible {
     }
 
     pub fn passage(&self, book: &str, chap: u16) -> Vec<&Verse> {
+        if let Some(store) = &self.store {
+            return store.passage(book, chap);
+        }
         self.verses
             .iter()
             .filter(|v| v.book == book && v.chapter == chap)
             .collect()
     }
+
+    /// The position closest to `book chap:verse` that this bible has: the
+    /// verse itself, else the last verse before it in the chapter, else the
+    /// nearest chapter of the book, else the first chapter of the bible.
+    pub fn nearest(&self, book: &str, chap: u16, verse: u16) -> Option<(String, u16, u16)> {
+        let chapters = self.chapters();
+        let (b, c) = chapters
+            .iter()
+            .filter(|(b, _)| b == book)
+            .min_by_key(|(_, c)| c.abs_diff(chap))
+            .or_else(|| chapters.first())?;
+        let verses = self.passage(b, *c);
+        let found = if b == book && *c == chap {
+            verses.iter().rev().find(|v| v.verse <= verse)
+        } else {
+            None
+        };
+        let v = found.or(verses.first()).map_or(1, |v| v.verse);
+        Some((b.clone(), *c, v))
+    }
+
+    /// The full-text index of this bible, built on first use.
+    /// Whether chapters are read from a database on demand rather than held
+    /// in memory.
+    pub fn is_stored(&self) -> bool {
+        self.store.is_some()
+    }
+
+    pub fn search_index(&self) -> &search::Index {
+        self.search.get_or_init(|| search::Index::build(self))
+    }
+
+    pub fn footnotes(&self, book: &str, chap: u16) -> Vec<&Footnote> {
+        self.footnotes
+            .iter()
+            .filter(|n| n.book == book && n.chapter == chap)
+            .collect()
+    }
+}
+
+/// 1-based line and column of a byte offset, for error messages.
+fn line_col(src: &str, pos: usize) -> (usize, usize) {
+    let before = &src[..pos.min(src.len())];
+    let line = before.matches('\n').count() + 1;
+    let col = before
+        .rsplit('\n')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .count()
+        + 1;
+    (line, col)
+}
+
+/// The USFM files of a project directory, sorted by name.
+pub(crate) fn usfm_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file())
+        .filter(|p| {
+            p.extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("usfm") || e.eq_ignore_ascii_case("sfm"))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn read(path: impl AsRef<Path>) -> Result<String> {
+    std::fs::read_to_string(&path).wrap_err_with(|| format!("cannot open {:?}", path.as_ref()))
+}
+
+fn attr(e: &BytesStart, name: &[u8]) -> Result<Option<String>> {
+    match e.try_get_attribute(name)? {
+        Some(a) => Ok(Some(a.unescape_value()?.into_owned())),
+        None => Ok(None),
+    }
+}
+
+/// Reduce the inline markup of SWORD and SQLite modules (OSIS, ThML, GBF,
+/// MyBible) to plain verse text, dropping notes, headings and Strong's numbers.
+fn strip_markup(src: &str) -> String {
+    let mut out = String::with_capacity(src.len());
+    let mut skip = 0usize;
+    let mut rest = src;
+    while let Some(lt) = rest.find('<') {
+        if skip == 0 {
+            out.push_str(&rest[..lt]);
+        }
+        let Some(gt) = rest[lt..].find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = &rest[lt + 1..lt + gt];
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+        let self_closing = tag.ends_with('/');
+        match name {
+            "note" | "title" | "RF" | "TS" | "S" | "f" | "n"
+                if !tag.starts_with('/') && !self_closing =>
+            {
+                skip += 1
+            }
+            "note" | "title" | "S" | "f" | "n" if tag.starts_with('/') => {
+                skip = skip.saturating_sub(1)
+            }
+            "Rf" | "Ts" => skip = skip.saturating_sub(1),
+            _ => {}
+        }
+        rest = &rest[lt + gt + 1..];
+    }
+    if skip == 0 {
+        out.push_str(rest);
+    }
+    let out = out
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_detect_format() {
+        let detect = |head| Format::detect(head).unwrap();
+        let osis = "<?xml version=\"1.0\"?>\n<!-- KJV -->\n<osis xmlns=\"x\"><osisText>";
+        assert_eq!(detect(osis), Format::Osis);
+        assert_eq!(detect("<usx version=\"3.0\">"), Format::Usx);
+        assert_eq!(detect("\u{feff}<XMLBIBLE>"), Format::Zefania);
+        assert_eq!(detect("<?xml?><bible><b n=\"Genesis\">"), Format::OpenSong);
+        assert_eq!(
+            detect("[KJV]\nDataPath=./modules/texts/ztext/kjv/\nModDrv=zText\n"),
+            Format::Sword
+        );
+        assert_eq!(detect("SQLite format 3\0\x10"), Format::Sqlite);
+        assert_eq!(detect("\\id GEN\n\\c 1"), Format::Usfm);
+        assert_eq!(detect("Genesis\tGe\t1\t1\t1\tIn"), Format::Tsv);
+        assert_eq!(detect("<?xml?>\n<osis:osis><osis:osisText>"), Format::Osis);
+        let err = Format::detect("<?xml?>\n<html><body>").unwrap_err();
+        assert_eq!(err.to_string(), "unrecognised XML with root element <html>");
+    }
+
+    #[test]
+    fn test_usfm_directory() {
+        let dir = std::env::temp_dir().join(format!("logos-usfm-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("01-TOB.usfm"), "\\id TOB\n\\c 1\n\\v 1 Tobit.\n").unwrap();
+        std::fs::write(dir.join("02-JHN.SFM"), "\\id JHN\n\\c 1\n\\v 1 John.\n").unwrap();
+        std::fs::write(dir.join("03-GEN.usfm"), "\\id GEN\n\\c 1\n\\v 1 Genesis.\n").unwrap();
+        std::fs::write(dir.join("readme.txt"), "not scripture").unwrap();
+        let bible = Bible::load(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        let books: Vec<_> = bible.unwrap().verses.into_iter().map(|v| v.book).collect();
+        assert_eq!(books, ["Genesis", "John", "TOB"]);
+    }
+
+    #[test]
+    fn test_empty_file_is_refused() {
+        let path = std::env::temp_dir().join(format!("logos-empty-{}.tsv", std::process::id()));
+        std::fs::write(&path, "\n\n").unwrap();
+        let err = Bible::load(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(err.to_string(), format!("no verses found in {path:?}"));
+    }
+
+    #[test]
+    fn test_nearest() {
+        let verse = |book: &str, chapter, verse| Verse {
+            book: book.into(),
+            chapter,
+            verse,
+            ..Default::default()
+        };
+        let bible = Bible {
+            verses: vec![
+                verse("Mark", 16, 8),
+                verse("Mark", 16, 9),
+                verse("John", 7, 52),
+                verse("John", 8, 12),
+            ],
+            ..Default::default()
+        };
+        let at = |b: &str, c, v| Some((b.to_owned(), c, v));
+        assert_eq!(bible.nearest("Mark", 16, 9), at("Mark", 16, 9));
+        assert_eq!(bible.nearest("Mark", 16, 20), at("Mark", 16, 9));
+        assert_eq!(bible.nearest("John", 7, 53), at("John", 7, 52));
+        assert_eq!(bible.nearest("John", 9, 1), at("John", 8, 12));
+        assert_eq!(bible.nearest("Tobit", 1, 1), at("Mark", 16, 8));
+        assert_eq!(Bible::default().nearest("John", 1, 1), None);
+    }
+
+    #[test]
+    fn test_strip_markup() {
+        assert_eq!(
+            strip_markup(
+                "<title type=\"psalm\">A Psalm</title><w lemma=\"H3068\">The LORD</w> is my \
+                 shepherd<note type=\"x\">n</note>; I &amp; thou<S>7462</S>"
+            ),
+            "The LORD is my shepherd; I & thou"
+        );
+    }
 }
@@ -0,0 +1,142 @@
+use std::ops::Range;
+
+use similar::{ChangeTag, TextDiff};
+
+use super::{Bible, Verse};
+
+/// Word-level differences between two versions of a text, as byte ranges:
+/// `removed` indexes the old text and `added` the new one.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WordDiff {
+    pub removed: Vec<Range<usize>>,
+    pub added: Vec<Range<usize>>,
+}
+
+/// Diff `old` against `new` word by word. Changes in whitespace alone are
+/// not reported.
+pub fn words(old: &str, new: &str) -> WordDiff {
+    let diff = TextDiff::from_words(old, new);
+    let mut out = WordDiff::default();
+    let (mut at_old, mut at_new) = (0, 0);
+    for change in diff.iter_all_changes() {
+        let len = change.value().len();
+        let blank = change.value().trim().is_empty();
+        match change.tag() {
+            ChangeTag::Equal => {
+                at_old += len;
+                at_new += len;
+            }
+            ChangeTag::Delete => {
+                if !blank {
+                    push(&mut out.removed, at_old..at_old + len);
+                }
+                at_old += len;
+            }
+            ChangeTag::Insert => {
+                if !blank {
+                    push(&mut out.added, at_new..at_new + len);
+                }
+                at_new += len;
+            }
+        }
+    }
+    out
+}
+
+/// Add a range, merging it with the previous one when only whitespace
+/// separated them.
+fn push(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+    match ranges.last_mut() {
+        Some(last) if range.start - last.end <= 1 => last.end = range.end,
+        _ => ranges.push(range),
+    }
+}
+
+/// Every `(chapter, verse)` of `book` whose text differs between the two
+/// bibles, including verses only one of them has.
+pub fn differing_verses(old: &Bible, new: &Bible, book: &str) -> Vec<(u16, u16)> {
+    let mut chapters: Vec<u16> = old
+        .chapters()
+        .into_iter()
+        .chain(new.chapters())
+        .filter(|(b, _)| b == book)
+        .map(|(_, c)| c)
+        .collect();
+    chapters.sort_unstable();
+    chapters.dedup();
+
+    let mut out = Vec::new();
+    for chapter in chapters {
+        let (a, b) = (old.passage(book, chapter), new.passage(book, chapter));
+        let mut verses: Vec<u16> = a.iter().chain(&b).map(|v| v.verse).collect();
+        verses.sort_unstable();
+        verses.dedup();
+        for verse in verses {
+            if words_of(&a, verse) != words_of(&b, verse) {
+                out.push((chapter, verse));
+            }
+        }
+    }
+    out
+}
+
+fn words_of<'a>(passage: &[&'a Verse], verse: u16) -> Option<Vec<&'a str>> {
+    passage
+        .iter()
+        .find(|v| v.verse == verse)
+        .map(|v| v.text.split_whitespace().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_words() {
+        let old = "And God said, Let there bee light";
+        let new = "And God said, Let there be light: and there was light";
+        let diff = words(old, new);
+        let text = |src: &'static str, ranges: Vec<Range<usize>>| {
+            ranges.into_iter().map(|r| &src[r]).collect::<Vec<_>>()
+        };
+        assert_eq!(text(old, diff.removed), vec!["bee"]);
+        assert_eq!(text(new, diff.added), vec!["be light: and there was"]);
+        assert_eq!(
+            words("in  the beginning", "in the beginning"),
+            WordDiff::default()
+        );
+    }
+
+    #[test]
+    fn test_differing_verses() {
+        let bible = |texts: &[(u16, &str)]| Bible {
+            verses: texts
+                .iter()
+                .map(|&(verse, text)| Verse {
+                    book: "Ruth".into(),
+                    chapter: 1,
+                    verse,
+                    text: text.into(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let old = bible(&[
+            (1, "Now it came"),
+            (2, "And the name"),
+            (3, "And Elimelech"),
+        ]);
+        let new = bible(&[
+            (1, "Now it came"),
+            (2, "And the names"),
+            (4, "And they took"),
+        ]);
+        assert_eq!(
+            differing_verses(&old, &new, "Ruth"),
+            vec![(1, 2), (1, 3), (1, 4)]
+        );
+    }
+}
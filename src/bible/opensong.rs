@@ -0,0 +1,111 @@
+use color_eyre::{Result, eyre::eyre};
+use quick_xml::{Reader, events::Event};
+
+use super::{Bible, Verse, attr, canon, line_col};
+
+/// Parse an OpenSong bible (`<bible><b n><c n><v n>`) into a [`Bible`].
+///
+/// OpenSong names books by string; names matching the canon table (by name
+/// or OSIS id) are normalised, anything else is kept as written.
+pub fn parse(src: &str) -> Result<Bible> {
+    let mut reader = Reader::from_str(src);
+    let mut out = Vec::new();
+    let mut book: Option<String> = None;
+    let mut chapter: Option<u16> = None;
+    let mut verse: Option<Verse> = None;
+
+    loop {
+        let pos = reader.buffer_position() as usize;
+        let at = |e: String| {
+            let (ln, col) = line_col(src, pos);
+            eyre!("line {ln}, column {col}: {e}")
+        };
+        let event = reader.read_event().map_err(|e| {
+            let (ln, col) = line_col(src, reader.error_position() as usize);
+            eyre!("line {ln}, column {col}: {e}")
+        })?;
+        match event {
+            Event::Start(e) => match e.name().as_ref() {
+                b"b" => {
+                    let name =
+                        attr(&e, b"n")?.ok_or_else(|| at("<b> without a book name".into()))?;
+                    book = Some(
+                        canon::BOOKS
+                            .iter()
+                            .find(|b| b.name.eq_ignore_ascii_case(&name))
+                            .or_else(|| canon::by_osis(&name))
+                            .map_or(name, |b| b.name.to_owned()),
+                    );
+                }
+                b"c" => {
+                    let number = attr(&e, b"n")?.unwrap_or_default();
+                    chapter = Some(
+                        number
+                            .parse()
+                            .map_err(|_| at(format!("bad chapter “{number}”")))?,
+                    );
+                }
+                b"v" => {
+                    let (Some(book), Some(chapter)) = (&book, chapter) else {
+                        return Err(at("<v> outside of a chapter".into()));
+                    };
+                    let number = attr(&e, b"n")?.unwrap_or_default();
+                    // Merged verses (`n="1-2"`) are stored under their first number.
+                    let first = number.split(['-', ',']).next().unwrap_or_default();
+                    verse = Some(Verse {
+                        book: book.clone(),
+                        chapter,
+                        verse: first
+                            .parse()
+                            .map_err(|_| at(format!("bad verse “{number}”")))?,
+                        ..Default::default()
+                    });
+                }
+                _ => {}
+            },
+            Event::End(e) if e.name().as_ref() == b"v" => {
+                if let Some(mut v) = verse.take() {
+                    v.text = v.text.split_whitespace().collect::<Vec<_>>().join(" ");
+                    out.push(v);
+                }
+            }
+            Event::Text(t) => {
+                if let Some(v) = &mut verse {
+                    v.text.push_str(&t.unescape()?);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(Bible {
+        verses: out,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_opensong() {
+        let bible = parse(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<bible>
+  <b n="Psalms"><c n="117"><v n="1">O praise the LORD, all ye nations:</v><v n="2">For his merciful kindness is great toward us.</v></c></b>
+  <b n="Tobit"><c n="1"><v n="1">The book of the words of Tobit</v></c></b>
+</bible>"#,
+        )
+        .unwrap();
+        assert_eq!(
+            bible.chapters(),
+            vec![("Psalms".into(), 117), ("Tobit".into(), 1)]
+        );
+        assert_eq!(bible.verses[1].verse, 2);
+        assert_eq!(bible.verses[2].text, "The book of the words of Tobit");
+    }
+}
@@ -0,0 +1,205 @@
+use color_eyre::{Result, eyre::eyre};
+use quick_xml::{
+    Reader,
+    events::{BytesStart, Event},
+};
+
+use super::{Bible, Verse, attr, canon, line_col};
+
+/// Parse an OSIS document into a [`Bible`].
+///
+/// Both container verses (`<verse osisID="Gen.1.1">…</verse>`) and milestone
+/// verses (`<verse sID=… osisID=…/>…<verse eID=…/>`) are supported. Notes are
+/// left out of the verse text.
+pub fn parse(src: &str) -> Result<Bible> {
+    let mut reader = Reader::from_str(src);
+    let mut out = Vec::new();
+    // The verse being collected, along with its milestone sID if it has one.
+    let mut open: Option<(Verse, Option<String>)> = None;
+    let mut note_depth = 0usize;
+
+    loop {
+        let pos = reader.buffer_position() as usize;
+        let event = reader.read_event().map_err(|e| {
+            let (ln, col) = line_col(src, reader.error_position() as usize);
+            eyre!("line {ln}, column {col}: {e}")
+        })?;
+        match event {
+            Event::Start(e) if e.local_name().as_ref() == b"verse" => {
+                let (verse, _) = start_verse(src, pos, &e, &open)?;
+                open = Some((verse, None));
+            }
+            Event::Empty(e) if e.local_name().as_ref() == b"verse" => {
+                if let Some(eid) = attr(&e, b"eID")? {
+                    match open.take() {
+                        Some((verse, Some(sid))) if sid == eid => out.push(finish(verse)),
+                        Some((verse, _)) => {
+                            let (ln, col) = line_col(src, pos);
+                            return Err(eyre!(
+                                "line {ln}, column {col}: verse end “{eid}” does not match open verse {} {}:{}",
+                                verse.book,
+                                verse.chapter,
+                                verse.verse
+                            ));
+                        }
+                        None => {
+                            let (ln, col) = line_col(src, pos);
+                            return Err(eyre!(
+                                "line {ln}, column {col}: verse end “{eid}” without a matching start"
+                            ));
+                        }
+                    }
+                } else {
+                    let (verse, sid) = start_verse(src, pos, &e, &open)?;
+                    let Some(sid) = sid else {
+                        let (ln, col) = line_col(src, pos);
+                        return Err(eyre!(
+                            "line {ln}, column {col}: empty <verse/> needs an sID or eID"
+                        ));
+                    };
+                    open = Some((verse, Some(sid)));
+                }
+            }
+            Event::End(e) if e.local_name().as_ref() == b"verse" => {
+                if let Some((verse, None)) = open.take() {
+                    out.push(finish(verse));
+                }
+            }
+            Event::Start(e) if e.local_name().as_ref() == b"note" => note_depth += 1,
+            Event::End(e) if e.local_name().as_ref() == b"note" => {
+                note_depth = note_depth.saturating_sub(1)
+            }
+            Event::Text(t) if note_depth == 0 => {
+                if let Some((verse, _)) = &mut open {
+                    verse.text.push_str(&t.unescape()?);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if let Some((verse, _)) = open {
+        return Err(eyre!(
+            "end of document: verse {} {}:{} is never closed",
+            verse.book,
+            verse.chapter,
+            verse.verse
+        ));
+    }
+    Ok(Bible {
+        verses: out,
+        ..Default::default()
+    })
+}
+
+fn start_verse(
+    src: &str,
+    pos: usize,
+    e: &BytesStart,
+    open: &Option<(Verse, Option<String>)>,
+) -> Result<(Verse, Option<String>)> {
+    let (ln, col) = line_col(src, pos);
+    let Some(id) = attr(e, b"osisID")? else {
+        return Err(eyre!("line {ln}, column {col}: <verse> without osisID"));
+    };
+    if let Some((prev, _)) = open {
+        return Err(eyre!(
+            "line {ln}, column {col}: verse “{id}” starts before {} {}:{} ends",
+            prev.book,
+            prev.chapter,
+            prev.verse
+        ));
+    }
+    // Merged verses carry several space-separated ids; the first one wins.
+    let first = id.split_whitespace().next().unwrap_or_default();
+    let parts: Vec<&str> = first.split('.').collect();
+    let [book, ch, vs] = parts.as_slice() else {
+        return Err(eyre!("line {ln}, column {col}: bad verse osisID “{id}”"));
+    };
+    let verse = Verse {
+        book: canon::osis_name(book),
+        chapter: ch
+            .parse()
+            .map_err(|_| eyre!("line {ln}, column {col}: bad chapter in osisID “{id}”"))?,
+        verse: vs
+            .parse()
+            .map_err(|_| eyre!("line {ln}, column {col}: bad verse in osisID “{id}”"))?,
+        ..Default::default()
+    };
+    Ok((verse, attr(e, b"sID")?))
+}
+
+fn finish(mut verse: Verse) -> Verse {
+    verse.text = verse.text.split_whitespace().collect::<Vec<_>>().join(" ");
+    verse
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_container_verses() {
+        let bible = parse(
+            r#"<osis><osisText><div type="book" osisID="Gen"><chapter osisID="Gen.1">
+<verse osisID="Gen.1.1">In the beginning <note>a note</note>God created
+the heaven and the earth.</verse>
+<verse osisID="Gen.1.2">And the earth was without form.</verse>
+</chapter></div></osisText></osis>"#,
+        )
+        .unwrap();
+        assert_eq!(bible.verses.len(), 2);
+        assert_eq!(bible.verses[0].book, "Genesis");
+        assert_eq!(
+            bible.verses[0].text,
+            "In the beginning God created the heaven and the earth."
+        );
+        assert_eq!(bible.verses[1].verse, 2);
+    }
+
+    #[test]
+    fn test_milestone_verses() {
+        let bible = parse(
+            r#"<osis><div type="book" osisID="John"><chapter sID="c" osisID="John.3"/>
+<verse sID="v16" osisID="John.3.16"/>For God so loved the world<verse eID="v16"/>
+<chapter eID="c"/></div></osis>"#,
+        )
+        .unwrap();
+        assert_eq!(bible.verses.len(), 1);
+        assert_eq!(bible.verses[0].book, "John");
+        assert_eq!(bible.verses[0].chapter, 3);
+        assert_eq!(bible.verses[0].verse, 16);
+        assert_eq!(bible.verses[0].text, "For God so loved the world");
+    }
+
+    #[test]
+    fn test_prefixed_elements() {
+        let bible = parse(
+            r#"<osis:osis xmlns:osis="http://www.bibletechnologies.net/2003/OSIS/namespace">
+<osis:verse osisID="Gen.1.1">In the beginning<osis:note>a note</osis:note></osis:verse>
+</osis:osis>"#,
+        )
+        .unwrap();
+        assert_eq!(bible.verses.len(), 1);
+        assert_eq!(bible.verses[0].text, "In the beginning");
+    }
+
+    #[test]
+    fn test_errors_carry_position() {
+        let err = parse("<osis>\n  <verse osisID=\"Gen.x.1\">text</verse></osis>").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 3: bad chapter in osisID “Gen.x.1”"
+        );
+
+        let err = parse("<osis>\n<verse sID=\"a\" osisID=\"Gen.1.1\"/>x<verse eID=\"b\"/></osis>")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 35: verse end “b” does not match open verse Genesis 1:1"
+        );
+    }
+}
@@ -0,0 +1,557 @@
+use std::fmt;
+
+use super::{Bible, Verse, canon};
+
+/// Common abbreviations that are neither a prefix of the book name nor its
+/// OSIS or USFM id.
+const ALIASES: &[(&str, &str)] = &[
+    ("gn", "Genesis"),
+    ("ex", "Exodus"),
+    ("lv", "Leviticus"),
+    ("nm", "Numbers"),
+    ("dt", "Deuteronomy"),
+    ("jdg", "Judges"),
+    ("jg", "Judges"),
+    ("rt", "Ruth"),
+    ("ps", "Psalms"),
+    ("pss", "Psalms"),
+    ("psalm", "Psalms"),
+    ("pr", "Proverbs"),
+    ("prv", "Proverbs"),
+    ("qoh", "Ecclesiastes"),
+    ("so", "Song of Solomon"),
+    ("sos", "Song of Solomon"),
+    ("songofsongs", "Song of Solomon"),
+    ("canticles", "Song of Solomon"),
+    ("ezk", "Ezekiel"),
+    ("jl", "Joel"),
+    ("am", "Amos"),
+    ("mt", "Matthew"),
+    ("mk", "Mark"),
+    ("mrk", "Mark"),
+    ("lk", "Luke"),
+    ("jn", "John"),
+    ("jhn", "John"),
+    ("phil", "Philippians"),
+    ("php", "Philippians"),
+    ("phm", "Philemon"),
+    ("philem", "Philemon"),
+    ("jas", "James"),
+    ("jm", "James"),
+    ("1jn", "1 John"),
+    ("2jn", "2 John"),
+    ("3jn", "3 John"),
+    ("jud", "Jude"),
+    ("rv", "Revelation"),
+    ("apocalypse", "Revelation"),
+];
+
+/// A place in a book; `verse` is `None` when the whole chapter is meant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Point {
+    pub chapter: u16,
+    pub verse: Option<u16>,
+}
+
+/// A contiguous stretch of one book, from `start` to `end` inclusive, e.g.
+/// `Genesis 1:26-2:3` or `Psalms 23`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Passage {
+    pub book: String,
+    pub start: Point,
+    pub end: Point,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReferenceError {
+    Empty,
+    /// A book name that matches no book; `at` is a byte offset in the input.
+    UnknownBook {
+        name: String,
+        at: usize,
+    },
+    /// An abbreviation that fits several books.
+    AmbiguousBook {
+        name: String,
+        candidates: Vec<String>,
+    },
+    MissingBook {
+        at: usize,
+    },
+    ExpectedNumber {
+        at: usize,
+    },
+    Unexpected {
+        found: char,
+        at: usize,
+    },
+    /// A range whose end comes before its start.
+    Backwards {
+        at: usize,
+    },
+    NoSuchBook(String),
+    NoSuchChapter {
+        book: String,
+        chapter: u16,
+    },
+    NoSuchVerse {
+        book: String,
+        chapter: u16,
+        verse: u16,
+    },
+}
+
+impl fmt::Display for ReferenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty reference"),
+            Self::UnknownBook { name, at } => write!(f, "column {}: unknown book “{name}”", at + 1),
+            Self::AmbiguousBook { name, candidates } => {
+                write!(f, "“{name}” could be {}", candidates.join(" or "))
+            }
+            Self::MissingBook { at } => write!(f, "column {}: expected a book name", at + 1),
+            Self::ExpectedNumber { at } => write!(f, "column {}: expected a number", at + 1),
+            Self::Unexpected { found, at } => write!(f, "column {}: unexpected “{found}”", at + 1),
+            Self::Backwards { at } => write!(f, "column {}: range ends before it starts", at + 1),
+            Self::NoSuchBook(book) => write!(f, "this translation has no {book}"),
+            Self::NoSuchChapter { book, chapter } => write!(f, "{book} has no chapter {chapter}"),
+            Self::NoSuchVerse {
+                book,
+                chapter,
+                verse,
+            } => write!(f, "{book} {chapter} has no verse {verse}"),
+        }
+    }
+}
+
+impl std::error::Error for ReferenceError {}
+
+impl fmt::Display for Passage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (s, e) = (self.start, self.end);
+        write!(f, "{} {}", self.book, s.chapter)?;
+        if let Some(v) = s.verse {
+            write!(f, ":{v}")?;
+        }
+        match (s.chapter == e.chapter, e.verse) {
+            (true, Some(v)) if s.verse != Some(v) => write!(f, "-{v}"),
+            (true, _) => Ok(()),
+            (false, Some(v)) => write!(f, "-{}:{v}", e.chapter),
+            (false, None) => write!(f, "-{}", e.chapter),
+        }
+    }
+}
+
+/// Parse a list of references such as `Jn 3:16-18; Rom 8:1,28`, `Gen 1:26-2:3`
+/// or `Ps 23`. Later items without a book name continue the previous book,
+/// and after a comma a lone number is a verse if the previous item named one.
+pub fn parse(input: &str) -> Result<Vec<Passage>, ReferenceError> {
+    if input.trim().is_empty() {
+        return Err(ReferenceError::Empty);
+    }
+    let mut parser = Parser { input, pos: 0 };
+    let mut out: Vec<Passage> = Vec::new();
+    let mut after_comma = false;
+    loop {
+        parser.skip_space();
+        let book_at = parser.pos;
+        let book = match parser.book()? {
+            Some(book) => book,
+            None => match out.last() {
+                Some(prev) => prev.book.clone(),
+                None => return Err(ReferenceError::MissingBook { at: book_at }),
+            },
+        };
+        let named = parser.pos != book_at;
+        let single = chapter_count(&book) == Some(1);
+
+        let first = parser.number()?;
+        let start = if parser.eat(':') {
+            Point {
+                chapter: first,
+                verse: Some(parser.number()?),
+            }
+        } else if !named && after_comma && out.last().is_some_and(|p| p.end.verse.is_some()) {
+            // `Rom 8:1,28`: a verse of the chapter the previous item ended in.
+            Point {
+                chapter: out.last().map_or(1, |p| p.end.chapter),
+                verse: Some(first),
+            }
+        } else if single {
+            // `Jude 3`: one-chapter books are cited by verse.
+            Point {
+                chapter: 1,
+                verse: Some(first),
+            }
+        } else {
+            Point {
+                chapter: first,
+                verse: None,
+            }
+        };
+
+        let mut end = start;
+        let dash_at = parser.pos;
+        if parser.eat('-') || parser.eat('–') {
+            let n = parser.number()?;
+            end = match start.verse {
+                Some(_) if parser.eat(':') => Point {
+                    chapter: n,
+                    verse: Some(parser.number()?),
+                },
+                Some(_) => Point {
+                    chapter: start.chapter,
+                    verse: Some(n),
+                },
+                None => Point {
+                    chapter: n,
+                    verse: None,
+                },
+            };
+            if end < start {
+                return Err(ReferenceError::Backwards { at: dash_at });
+            }
+        }
+        out.push(Passage { book, start, end });
+
+        parser.skip_space();
+        match parser.next() {
+            None => return Ok(out),
+            Some(';') => after_comma = false,
+            Some(',') => after_comma = true,
+            Some(found) => {
+                return Err(ReferenceError::Unexpected {
+                    found,
+                    at: parser.pos - found.len_utf8(),
+                });
+            }
+        }
+    }
+}
+
+impl Passage {
+    /// The verses of the passage in `bible`, checking that its first and last
+    /// chapter and verse exist.
+    pub fn resolve<'a>(&self, bible: &'a Bible) -> Result<Vec<&'a Verse>, ReferenceError> {
+        let chapters: Vec<u16> = bible
+            .chapters()
+            .into_iter()
+            .filter(|(b, _)| *b == self.book)
+            .map(|(_, c)| c)
+            .collect();
+        if chapters.is_empty() {
+            return Err(ReferenceError::NoSuchBook(self.book.clone()));
+        }
+        for point in [self.start, self.end] {
+            if !chapters.contains(&point.chapter) {
+                return Err(ReferenceError::NoSuchChapter {
+                    book: self.book.clone(),
+                    chapter: point.chapter,
+                });
+            }
+            if let Some(verse) = point.verse
+                && !bible
+                    .passage(&self.book, point.chapter)
+                    .iter()
+                    .any(|v| v.verse == verse)
+            {
+                return Err(ReferenceError::NoSuchVerse {
+                    book: self.book.clone(),
+                    chapter: point.chapter,
+                    verse,
+                });
+            }
+        }
+
+        let from = (self.start.chapter, self.start.verse.unwrap_or(0));
+        let to = (self.end.chapter, self.end.verse.unwrap_or(u16::MAX));
+        Ok(chapters
+            .into_iter()
+            .filter(|c| (from.0..=to.0).contains(c))
+            .flat_map(|c| bible.passage(&self.book, c))
+            .filter(|v| (from..=to).contains(&(v.chapter, v.verse)))
+            .collect())
+    }
+}
+
+/// Something put on a run of verses of one chapter, such as a note or a tag.
+pub trait VerseRange {
+    /// Its book, chapter, and first and last verse.
+    fn range(&self) -> (&str, u16, u16, u16);
+
+    /// The verses it is on, as a reference such as `John 3:16-18`.
+    fn reference(&self) -> String {
+        let (book, chapter, verse, end) = self.range();
+        let mut reference = format!("{book} {chapter}:{verse}");
+        if end != verse {
+            reference.push_str(&format!("-{end}"));
+        }
+        reference
+    }
+
+    fn covers(&self, book: &str, chapter: u16, verse: u16) -> bool {
+        let (b, c, first, last) = self.range();
+        b == book && c == chapter && (first..=last).contains(&verse)
+    }
+
+    /// Where it falls in bible order.
+    fn position(&self) -> (usize, &str, u16, u16, u16) {
+        let (book, chapter, verse, end) = self.range();
+        (canon::order(book), book, chapter, verse, end)
+    }
+}
+
+/// Find a canon book by name, id or abbreviation, ignoring case, spaces and
+/// dots; `1 Jn`, `I John` and `First John` all name 1 John.
+pub fn book(name: &str) -> Result<&'static str, ReferenceError> {
+    let key = normalize(name);
+    let unknown = || ReferenceError::UnknownBook {
+        name: name.trim().to_owned(),
+        at: 0,
+    };
+    if key.is_empty() {
+        return Err(unknown());
+    }
+    let exact = canon::BOOKS.iter().find(|b| {
+        normalize(b.name) == key
+            || b.osis.eq_ignore_ascii_case(&key)
+            || b.usfm.eq_ignore_ascii_case(&key)
+    });
+    if let Some(b) = exact {
+        return Ok(b.name);
+    }
+    if let Some((_, name)) = ALIASES.iter().find(|(alias, _)| *alias == key) {
+        return Ok(name);
+    }
+    let matches: Vec<&str> = canon::BOOKS
+        .iter()
+        .filter(|b| normalize(b.name).starts_with(&key))
+        .map(|b| b.name)
+        .collect();
+    match matches.as_slice() {
+        [one] => Ok(one),
+        [] => Err(unknown()),
+        many => Err(ReferenceError::AmbiguousBook {
+            name: name.trim().to_owned(),
+            candidates: many.iter().map(|s| s.to_string()).collect(),
+        }),
+    }
+}
+
+/// Lowercase, drop spaces and dots, and spell leading ordinals as digits.
+fn normalize(name: &str) -> String {
+    let lower = name.trim().to_lowercase();
+    let mut words: Vec<&str> = lower.split([' ', '.']).filter(|w| !w.is_empty()).collect();
+    let ordinal = match words.first().copied() {
+        Some("i" | "first" | "1st") => Some("1"),
+        Some("ii" | "second" | "2nd") => Some("2"),
+        Some("iii" | "third" | "3rd") => Some("3"),
+        _ => None,
+    };
+    if let Some(digit) = ordinal
+        && words.len() > 1
+    {
+        words[0] = digit;
+    }
+    words.concat()
+}
+
+fn chapter_count(book: &str) -> Option<usize> {
+    let i = canon::BOOKS.iter().position(|b| b.name == book)?;
+    canon::KJV_VERSES.get(i).map(|c| c.len())
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_space(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.rest().chars().next()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_space();
+        if self.rest().starts_with(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// A book name, if the item starts with one: letters, spaces and dots,
+    /// optionally after an ordinal digit (`1 John`).
+    fn book(&mut self) -> Result<Option<String>, ReferenceError> {
+        let rest = self.rest();
+        let mut end = 0;
+        let mut chars = rest.char_indices().peekable();
+        if let Some(&(_, c)) = chars.peek()
+            && c.is_ascii_digit()
+        {
+            let after = rest[1..].trim_start_matches([' ', '.']);
+            if !after.starts_with(char::is_alphabetic) {
+                return Ok(None);
+            }
+            chars.next();
+            end = 1;
+        }
+        for (i, c) in chars {
+            if c.is_alphabetic() || c == ' ' || c == '.' {
+                end = i + c.len_utf8();
+            } else {
+                break;
+            }
+        }
+        let name = rest[..end].trim();
+        if name.is_empty() {
+            return Ok(None);
+        }
+        let at = self.pos;
+        let found = book(name).map_err(|e| match e {
+            ReferenceError::UnknownBook { name, .. } => ReferenceError::UnknownBook { name, at },
+            e => e,
+        })?;
+        self.pos += end;
+        Ok(Some(found.to_owned()))
+    }
+
+    fn number(&mut self) -> Result<u16, ReferenceError> {
+        self.skip_space();
+        let digits = self
+            .rest()
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest().len());
+        let n = self.rest()[..digits]
+            .parse()
+            .ok()
+            .filter(|&n| n > 0)
+            .ok_or(ReferenceError::ExpectedNumber { at: self.pos })?;
+        self.pos += digits;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn refs(input: &str) -> Vec<String> {
+        parse(input)
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_books() {
+        assert_eq!(book("Gen"), Ok("Genesis"));
+        assert_eq!(book("jn"), Ok("John"));
+        assert_eq!(book("1 Jn"), Ok("1 John"));
+        assert_eq!(book("I John"), Ok("1 John"));
+        assert_eq!(book("Second Kgs"), Ok("2 Kings"));
+        assert_eq!(book("1SA"), Ok("1 Samuel"));
+        assert_eq!(book("song of songs"), Ok("Song of Solomon"));
+        assert_eq!(book("Rom."), Ok("Romans"));
+        assert_eq!(
+            book("Ju"),
+            Err(ReferenceError::AmbiguousBook {
+                name: "Ju".into(),
+                candidates: vec!["Judges".into(), "Jude".into()],
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            refs("Jn 3:16-18; Rom 8:1,28"),
+            vec!["John 3:16-18", "Romans 8:1", "Romans 8:28"]
+        );
+        assert_eq!(refs("Gen 1:26-2:3"), vec!["Genesis 1:26-2:3"]);
+        assert_eq!(refs("ps 23"), vec!["Psalms 23"]);
+        assert_eq!(refs("Gen 1-3, 5"), vec!["Genesis 1-3", "Genesis 5"]);
+        assert_eq!(refs("Jude 3"), vec!["Jude 1:3"]);
+        assert_eq!(
+            refs("Mt 5:3; 6:9-13"),
+            vec!["Matthew 5:3", "Matthew 6:9-13"]
+        );
+        assert_eq!(refs("1 Cor 13"), vec!["1 Corinthians 13"]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse(" "), Err(ReferenceError::Empty));
+        assert_eq!(
+            parse("Jn 3:16; Xyz 1"),
+            Err(ReferenceError::UnknownBook {
+                name: "Xyz".into(),
+                at: 9
+            })
+        );
+        assert_eq!(parse("3:16"), Err(ReferenceError::MissingBook { at: 0 }));
+        assert_eq!(
+            parse("Jn 3:"),
+            Err(ReferenceError::ExpectedNumber { at: 5 })
+        );
+        assert_eq!(
+            parse("Jn 3:18-16"),
+            Err(ReferenceError::Backwards { at: 7 })
+        );
+        assert_eq!(
+            parse("Jn 3:16 x").unwrap_err().to_string(),
+            "column 9: unexpected “x”"
+        );
+    }
+
+    #[test]
+    fn test_resolve() {
+        let verse = |chapter, verse| Verse {
+            book: "Genesis".into(),
+            chapter,
+            verse,
+            ..Default::default()
+        };
+        let bible = Bible {
+            verses: vec![verse(1, 30), verse(1, 31), verse(2, 1), verse(2, 2)],
+            ..Default::default()
+        };
+        let resolve = |input: &str| {
+            parse(input).unwrap()[0]
+                .resolve(&bible)
+                .map(|vs| vs.iter().map(|v| (v.chapter, v.verse)).collect::<Vec<_>>())
+        };
+        assert_eq!(resolve("Gen 1:31-2:1"), Ok(vec![(1, 31), (2, 1)]));
+        assert_eq!(resolve("Gen 2"), Ok(vec![(2, 1), (2, 2)]));
+        assert_eq!(
+            resolve("Gen 3"),
+            Err(ReferenceError::NoSuchChapter {
+                book: "Genesis".into(),
+                chapter: 3
+            })
+        );
+        assert_eq!(
+            resolve("Gen 2:9").unwrap_err().to_string(),
+            "Genesis 2 has no verse 9"
+        );
+        assert_eq!(
+            resolve("Ex 1"),
+            Err(ReferenceError::NoSuchBook("Exodus".into()))
+        );
+    }
+}
@@ -0,0 +1,691 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+    ops::Range,
+};
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use strum::Display;
+use unicode_normalization::{UnicodeNormalization, char::is_combining_mark};
+
+use super::Bible;
+
+/// Inverted index over the words of every verse in a bible.
+#[derive(Debug, Default, Clone)]
+pub struct Index {
+    /// Book, chapter and verse of each indexed verse, by id.
+    verses: Vec<(String, u16, u16)>,
+    /// Every `(verse id, word position)` a word occurs at, in order.
+    words: HashMap<String, Vec<(u32, u32)>>,
+    /// The words of `words` under each accent-folded spelling.
+    folds: HashMap<String, Vec<String>>,
+}
+
+/// How the text of a search is matched.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[strum(serialize_all = "lowercase")]
+pub enum Mode {
+    /// A [`Query`], ignoring case.
+    #[default]
+    Words,
+    /// A [`Query`] that also ignores accents, breathings and cantillation
+    /// marks, for Greek and Hebrew.
+    Folded,
+    /// A regular expression over each verse, ignoring case.
+    Regex,
+}
+
+impl Mode {
+    pub fn next(self) -> Self {
+        match self {
+            Mode::Words => Mode::Folded,
+            Mode::Folded => Mode::Regex,
+            Mode::Regex => Mode::Words,
+        }
+    }
+}
+
+/// A search compiled for its mode, to find verses or mark matches in one.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Query { query: Query, folded: bool },
+    Regex(Regex),
+}
+
+/// A parsed search query.
+///
+/// Plain terms match any word that contains them, `=term` only the whole
+/// word and `"quoted words"` those words in a row. Terms next to each other
+/// must all match; `OR` (or `|`) and `NOT` (or `-`) combine them, and
+/// parentheses group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    Word(String),
+    Exact(String),
+    Phrase(Vec<String>),
+    Not(Box<Query>),
+    And(Vec<Query>),
+    Or(Vec<Query>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryError {
+    Empty,
+    /// A quote or parenthesis opened at `at` is never closed.
+    Unclosed {
+        at: usize,
+    },
+    Unexpected {
+        found: String,
+        at: usize,
+    },
+    /// An operator at `at` has nothing to apply to.
+    MissingTerm {
+        at: usize,
+    },
+    Regex(String),
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty search"),
+            Self::Unclosed { at } => write!(f, "column {}: never closed", at + 1),
+            Self::Unexpected { found, at } => write!(f, "column {}: unexpected “{found}”", at + 1),
+            Self::MissingTerm { at } => write!(f, "column {}: expected a search term", at + 1),
+            Self::Regex(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// The words of `text`, lowercased, with their byte ranges. Combining marks
+/// belong to the word they sit on.
+pub fn words(text: &str) -> Vec<(Range<usize>, String)> {
+    let mut out = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        match (start, c.is_alphanumeric() || is_combining_mark(c)) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                out.push((s..i, text[s..i].to_lowercase()));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// `word` without its diacritics: accents, Greek breathings, Hebrew vowel
+/// points and cantillation marks.
+pub fn fold(word: &str) -> String {
+    word.nfd().filter(|&c| !is_combining_mark(c)).collect()
+}
+
+impl Index {
+    pub fn build(bible: &Bible) -> Self {
+        let mut index = Self::default();
+        for (book, chapter) in bible.chapters() {
+            for v in bible.passage(&book, chapter) {
+                let id = index.verses.len() as u32;
+                index.verses.push((v.book.clone(), v.chapter, v.verse));
+                for (pos, (_, word)) in words(&v.text).into_iter().enumerate() {
+                    index.words.entry(word).or_default().push((id, pos as u32));
+                }
+            }
+        }
+        for word in index.words.keys() {
+            index
+                .folds
+                .entry(fold(word))
+                .or_default()
+                .push(word.clone());
+        }
+        index
+    }
+
+    /// Book, chapter and verse of a verse id from [`Index::search`].
+    pub fn verse(&self, id: u32) -> (&str, u16, u16) {
+        let (book, chapter, verse) = &self.verses[id as usize];
+        (book, *chapter, *verse)
+    }
+
+    /// Ids of the verses matching `query`, in bible order. A `folded`
+    /// query must have its terms folded already.
+    pub fn search(&self, query: &Query, folded: bool) -> Vec<u32> {
+        self.eval(query, folded).into_iter().collect()
+    }
+
+    /// Where `word`, or with `folded` any word folding to it, occurs, as
+    /// `(verse id, word position)` in order.
+    pub fn occurrences(&self, word: &str, folded: bool) -> Vec<(u32, u32)> {
+        if !folded {
+            return self.words.get(word).cloned().unwrap_or_default();
+        }
+        let mut out: Vec<(u32, u32)> = self
+            .folds
+            .get(word)
+            .into_iter()
+            .flatten()
+            .flat_map(|w| self.words[w].iter().copied())
+            .collect();
+        out.sort_unstable();
+        out
+    }
+
+    fn eval(&self, query: &Query, folded: bool) -> BTreeSet<u32> {
+        let ids = |word: &str| {
+            self.occurrences(word, folded)
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect::<BTreeSet<u32>>()
+        };
+        match query {
+            Query::Word(term) => {
+                let vocabulary: Box<dyn Iterator<Item = &String>> = if folded {
+                    Box::new(self.folds.keys())
+                } else {
+                    Box::new(self.words.keys())
+                };
+                vocabulary
+                    .filter(|w| w.contains(term.as_str()))
+                    .flat_map(|w| ids(w))
+                    .collect()
+            }
+            Query::Exact(word) => ids(word),
+            Query::Phrase(phrase) => self.phrase(phrase, folded),
+            Query::Not(q) => {
+                let hits = self.eval(q, folded);
+                (0..self.verses.len() as u32)
+                    .filter(|id| !hits.contains(id))
+                    .collect()
+            }
+            Query::And(qs) => {
+                let mut sets = qs.iter().map(|q| self.eval(q, folded));
+                let first = sets.next().unwrap_or_default();
+                sets.fold(first, |acc, s| &acc & &s)
+            }
+            Query::Or(qs) => qs.iter().flat_map(|q| self.eval(q, folded)).collect(),
+        }
+    }
+
+    fn phrase(&self, phrase: &[String], folded: bool) -> BTreeSet<u32> {
+        let postings: Vec<Vec<(u32, u32)>> =
+            phrase.iter().map(|w| self.occurrences(w, folded)).collect();
+        let Some((first, rest)) = postings.split_first() else {
+            return BTreeSet::new();
+        };
+        first
+            .iter()
+            .filter(|&&(id, pos)| {
+                rest.iter()
+                    .zip(1..)
+                    .all(|(p, i)| p.binary_search(&(id, pos + i)).is_ok())
+            })
+            .map(|&(id, _)| id)
+            .collect()
+    }
+}
+
+impl Pattern {
+    pub fn new(input: &str, mode: Mode) -> Result<Self, QueryError> {
+        match mode {
+            Mode::Words => Ok(Pattern::Query {
+                query: Query::parse(input)?,
+                folded: false,
+            }),
+            Mode::Folded => Ok(Pattern::Query {
+                query: Query::parse(input)?.folded(),
+                folded: true,
+            }),
+            Mode::Regex if input.trim().is_empty() => Err(QueryError::Empty),
+            Mode::Regex => RegexBuilder::new(input)
+                .case_insensitive(true)
+                .build()
+                .map(Pattern::Regex)
+                .map_err(|e| QueryError::Regex(e.to_string())),
+        }
+    }
+
+    /// Ids in the bible's [`Index`] of the verses that match, in order.
+    pub fn search(&self, bible: &Bible) -> Vec<u32> {
+        match self {
+            Pattern::Query { query, folded } => bible.search_index().search(query, *folded),
+            Pattern::Regex(re) => bible
+                .chapters()
+                .into_iter()
+                .flat_map(|(book, chapter)| {
+                    bible
+                        .passage(&book, chapter)
+                        .into_iter()
+                        .map(|v| re.is_match(&v.text))
+                        .collect::<Vec<_>>()
+                })
+                .zip(0..)
+                .filter_map(|(hit, id)| hit.then_some(id))
+                .collect(),
+        }
+    }
+
+    /// Byte ranges of `text` that the search matched, for highlighting.
+    /// Terms under a `NOT` are not marked.
+    pub fn matches(&self, text: &str) -> Vec<Range<usize>> {
+        let (query, folded) = match self {
+            Pattern::Regex(re) => {
+                return re
+                    .find_iter(text)
+                    .map(|m| m.range())
+                    .filter(|r| !r.is_empty())
+                    .collect();
+            }
+            Pattern::Query { query, folded } => (query, *folded),
+        };
+        let words = words(text);
+        let keys: Vec<String> = words
+            .iter()
+            .map(|(_, w)| if folded { fold(w) } else { w.clone() })
+            .collect();
+        let mut out = Vec::new();
+        for term in query.terms() {
+            match term {
+                Query::Word(t) => out.extend(
+                    (0..keys.len())
+                        .filter(|&i| keys[i].contains(t.as_str()))
+                        .map(|i| words[i].0.clone()),
+                ),
+                Query::Exact(w) => out.extend(
+                    (0..keys.len())
+                        .filter(|&i| keys[i] == *w)
+                        .map(|i| words[i].0.clone()),
+                ),
+                Query::Phrase(p) => out.extend(
+                    keys.windows(p.len())
+                        .enumerate()
+                        .filter(|(_, window)| window == p)
+                        .map(|(i, _)| words[i].0.start..words[i + p.len() - 1].0.end),
+                ),
+                _ => {}
+            }
+        }
+        out.sort_by_key(|r| r.start);
+        out
+    }
+}
+
+impl Query {
+    /// The words, exact words and phrases the query looks for, leaving out
+    /// those it excludes.
+    fn terms(&self) -> Vec<&Query> {
+        match self {
+            Query::Not(_) => Vec::new(),
+            Query::And(qs) | Query::Or(qs) => qs.iter().flat_map(Query::terms).collect(),
+            term => vec![term],
+        }
+    }
+
+    /// The query with every term folded, for [`Mode::Folded`].
+    fn folded(self) -> Self {
+        match self {
+            Query::Word(w) => Query::Word(fold(&w)),
+            Query::Exact(w) => Query::Exact(fold(&w)),
+            Query::Phrase(p) => Query::Phrase(p.iter().map(|w| fold(w)).collect()),
+            Query::Not(q) => Query::Not(Box::new(q.folded())),
+            Query::And(qs) => Query::And(qs.into_iter().map(Query::folded).collect()),
+            Query::Or(qs) => Query::Or(qs.into_iter().map(Query::folded).collect()),
+        }
+    }
+
+    pub fn parse(input: &str) -> Result<Self, QueryError> {
+        let mut parser = Parser {
+            tokens: lex(input)?,
+            at: 0,
+            end: input.len(),
+        };
+        if parser.tokens.is_empty() {
+            return Err(QueryError::Empty);
+        }
+        let query = parser.or()?;
+        match parser.tokens.get(parser.at) {
+            Some((at, token)) => Err(QueryError::Unexpected {
+                found: token.to_string(),
+                at: *at,
+            }),
+            None => Ok(query),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    Or,
+    And,
+    Not,
+    Term(String),
+    Exact(String),
+    Quoted(String),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Open => write!(f, "("),
+            Self::Close => write!(f, ")"),
+            Self::Or => write!(f, "OR"),
+            Self::And => write!(f, "AND"),
+            Self::Not => write!(f, "NOT"),
+            Self::Term(t) => write!(f, "{t}"),
+            Self::Exact(t) => write!(f, "={t}"),
+            Self::Quoted(t) => write!(f, "\"{t}\""),
+        }
+    }
+}
+
+fn lex(input: &str) -> Result<Vec<(usize, Token)>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some((at, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '|' => Token::Or,
+            '-' => Token::Not,
+            '"' => {
+                let rest = &input[at + 1..];
+                let len = rest.find('"').ok_or(QueryError::Unclosed { at })?;
+                while chars.next_if(|&(i, _)| i <= at + 1 + len).is_some() {}
+                Token::Quoted(rest[..len].to_owned())
+            }
+            _ => {
+                let mut end = input.len();
+                while let Some(&(i, c)) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '(' | ')' | '|' | '"') {
+                        end = i;
+                        break;
+                    }
+                    chars.next();
+                }
+                match &input[at..end] {
+                    "OR" => Token::Or,
+                    "AND" => Token::And,
+                    "NOT" => Token::Not,
+                    word => match word.strip_prefix('=') {
+                        Some(word) => Token::Exact(word.to_owned()),
+                        None => Token::Term(word.to_owned()),
+                    },
+                }
+            }
+        };
+        tokens.push((at, token));
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    at: usize,
+    /// Length of the input, where a missing term is reported at the end.
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.at).map(|(_, t)| t)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.at).map_or(self.end, |(at, _)| *at)
+    }
+
+    fn or(&mut self) -> Result<Query, QueryError> {
+        let mut qs = vec![self.and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.at += 1;
+            qs.push(self.and()?);
+        }
+        Ok(if qs.len() == 1 {
+            qs.remove(0)
+        } else {
+            Query::Or(qs)
+        })
+    }
+
+    fn and(&mut self) -> Result<Query, QueryError> {
+        let mut qs = vec![self.unary()?];
+        loop {
+            match self.peek() {
+                None | Some(Token::Or | Token::Close) => break,
+                Some(Token::And) => self.at += 1,
+                _ => {}
+            }
+            qs.push(self.unary()?);
+        }
+        Ok(if qs.len() == 1 {
+            qs.remove(0)
+        } else {
+            Query::And(qs)
+        })
+    }
+
+    fn unary(&mut self) -> Result<Query, QueryError> {
+        if self.peek() == Some(&Token::Not) {
+            self.at += 1;
+            return Ok(Query::Not(Box::new(self.unary()?)));
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Query, QueryError> {
+        let at = self.position();
+        let Some(token) = self.peek().cloned() else {
+            return Err(QueryError::MissingTerm { at });
+        };
+        self.at += 1;
+        let phrase = |text: &str| -> Result<Query, QueryError> {
+            let mut ws: Vec<String> = words(text).into_iter().map(|(_, w)| w).collect();
+            match ws.len() {
+                0 => Err(QueryError::MissingTerm { at }),
+                1 => Ok(Query::Exact(ws.remove(0))),
+                _ => Ok(Query::Phrase(ws)),
+            }
+        };
+        match token {
+            Token::Open => {
+                let query = self.or()?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(QueryError::Unclosed { at });
+                }
+                self.at += 1;
+                Ok(query)
+            }
+            Token::Term(term) => match phrase(&term)? {
+                Query::Exact(word) => Ok(Query::Word(word)),
+                query => Ok(query),
+            },
+            Token::Exact(word) | Token::Quoted(word) => phrase(&word),
+            Token::Close | Token::Or | Token::And | Token::Not => {
+                Err(QueryError::MissingTerm { at })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::bible::Verse;
+
+    fn bible() -> Bible {
+        let verse = |book: &str, verse, text: &str| Verse {
+            book: book.into(),
+            chapter: 1,
+            verse,
+            text: text.into(),
+            ..Default::default()
+        };
+        Bible {
+            verses: vec![
+                verse(
+                    "Genesis",
+                    1,
+                    "In the beginning God created the heaven and the earth.",
+                ),
+                verse("Genesis", 2, "And the earth was without form, and void."),
+                verse(
+                    "John",
+                    1,
+                    "In the beginning was the Word, and the Word was with God.",
+                ),
+                verse("John", 2, "The same was in the beginning with God."),
+                verse("1 John", 1, "God is love; he that loveth is beloved."),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn search(bible: &Bible, mode: Mode, query: &str) -> Vec<String> {
+        let index = bible.search_index();
+        Pattern::new(query, mode)
+            .unwrap()
+            .search(bible)
+            .into_iter()
+            .map(|id| {
+                let (book, chapter, verse) = index.verse(id);
+                format!("{book} {chapter}:{verse}")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_search() {
+        let bible = bible();
+        assert_eq!(
+            search(&bible, Mode::Words, "earth"),
+            vec!["Genesis 1:1", "Genesis 1:2"]
+        );
+        assert_eq!(search(&bible, Mode::Words, "love"), vec!["1 John 1:1"]);
+        assert_eq!(search(&bible, Mode::Words, "=love"), vec!["1 John 1:1"]);
+        assert_eq!(search(&bible, Mode::Words, "=lov"), Vec::<String>::new());
+        assert_eq!(
+            search(&bible, Mode::Words, "\"the beginning God\""),
+            vec!["Genesis 1:1"]
+        );
+        assert_eq!(
+            search(&bible, Mode::Words, "beginning god"),
+            search(&bible, Mode::Words, "beginning AND god")
+        );
+        assert_eq!(
+            search(&bible, Mode::Words, "beginning -word"),
+            vec!["Genesis 1:1", "John 1:2"]
+        );
+        assert_eq!(
+            search(&bible, Mode::Words, "void | loveth"),
+            vec!["Genesis 1:2", "1 John 1:1"]
+        );
+        assert_eq!(
+            search(&bible, Mode::Words, "NOT (god OR earth)"),
+            Vec::<String>::new()
+        );
+        assert_eq!(search(&bible, Mode::Words, "God's"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_modes() {
+        let verse = |book: &str, text: &str| Verse {
+            book: book.into(),
+            chapter: 1,
+            verse: 1,
+            text: text.into(),
+            ..Default::default()
+        };
+        let bible = Bible {
+            verses: vec![
+                verse("Genesis", "בְּרֵאשִׁ֖ית בָּרָ֣א אֱלֹהִ֑ים"),
+                verse("John", "Ἐν ἀρχῇ ἦν ὁ λόγος"),
+                verse("Acts", "Ἐν ἀρχῇ? no: the former treatise"),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(search(&bible, Mode::Words, "αρχη"), Vec::<String>::new());
+        assert_eq!(
+            search(&bible, Mode::Folded, "αρχη"),
+            vec!["John 1:1", "Acts 1:1"]
+        );
+        assert_eq!(
+            search(&bible, Mode::Folded, "\"εν αρχη ην\""),
+            vec!["John 1:1"]
+        );
+        assert_eq!(search(&bible, Mode::Folded, "=בראשית"), vec!["Genesis 1:1"]);
+        assert_eq!(
+            search(&bible, Mode::Regex, r"former\s+TREAT"),
+            vec!["Acts 1:1"]
+        );
+        assert!(matches!(
+            Pattern::new("(", Mode::Regex),
+            Err(QueryError::Regex(_))
+        ));
+    }
+
+    #[test]
+    fn test_matches() {
+        let text = "In the beginning was the Word, and the Word was with God.";
+        let marked = |query: &str, mode| {
+            Pattern::new(query, mode)
+                .unwrap()
+                .matches(text)
+                .into_iter()
+                .map(|r| &text[r])
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(marked("word -god", Mode::Words), vec!["Word", "Word"]);
+        assert_eq!(
+            marked("\"the word\" | =was", Mode::Words),
+            vec!["was", "the Word", "the Word", "was"]
+        );
+        assert_eq!(marked(r"w\w+d\b", Mode::Regex), vec!["Word", "Word"]);
+        let greek = "Ἐν ἀρχῇ ἦν ὁ λόγος";
+        let pattern = Pattern::new("λογος", Mode::Folded).unwrap();
+        assert_eq!(
+            pattern
+                .matches(greek)
+                .into_iter()
+                .map(|r| &greek[r])
+                .collect::<Vec<_>>(),
+            vec!["λόγος"]
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let word = |w: &str| Query::Word(w.into());
+        assert_eq!(
+            Query::parse("a b OR NOT c").unwrap(),
+            Query::Or(vec![
+                Query::And(vec![word("a"), word("b")]),
+                Query::Not(Box::new(word("c"))),
+            ])
+        );
+        assert_eq!(
+            Query::parse("=Light \"Let there\"").unwrap(),
+            Query::And(vec![
+                Query::Exact("light".into()),
+                Query::Phrase(vec!["let".into(), "there".into()]),
+            ])
+        );
+        assert_eq!(Query::parse("  "), Err(QueryError::Empty));
+        assert_eq!(Query::parse("\"open"), Err(QueryError::Unclosed { at: 0 }));
+        assert_eq!(Query::parse("(a b"), Err(QueryError::Unclosed { at: 0 }));
+        assert_eq!(Query::parse("a OR"), Err(QueryError::MissingTerm { at: 4 }));
+        assert_eq!(
+            Query::parse("a )").unwrap_err().to_string(),
+            "column 3: unexpected “)”"
+        );
+    }
+}
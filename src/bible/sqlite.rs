@@ -0,0 +1,295 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Mutex, OnceLock},
+};
+
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use rusqlite::{Connection, OpenFlags};
+
+use super::{Verse, canon, strip_markup};
+
+/// MyBible book numbers, in canon order (Genesis is 10, Revelation 730).
+const MYBIBLE_NUMBERS: [i64; 66] = [
+    10, 20, 30, 40, 50, 60, 70, 80, 90, 100, 110, 120, 130, 140, 150, 160, 190, 220, 230, 240, 250,
+    260, 290, 300, 310, 330, 340, 350, 360, 370, 380, 390, 400, 410, 420, 430, 440, 450, 460, 470,
+    480, 490, 500, 510, 520, 530, 540, 550, 560, 570, 580, 590, 600, 610, 620, 630, 640, 650, 660,
+    670, 680, 690, 700, 710, 720, 730,
+];
+
+/// A bible kept in an SQLite database and read one chapter at a time.
+///
+/// Understands the MySword (`Bible(Book, Chapter, Verse, Scripture)`),
+/// MyBible (`verses(book_number, …)`) and scrollmapper (`t_kjv(b, c, v, t)`,
+/// `KJV_verses(book_id, …)`) layouts.
+#[derive(Debug)]
+pub struct Store {
+    conn: Mutex<Connection>,
+    query: String,
+    chapters: Vec<Chapter>,
+}
+
+#[derive(Debug)]
+struct Chapter {
+    book: String,
+    id: i64,
+    chapter: u16,
+    verses: OnceLock<Vec<Verse>>,
+}
+
+/// Where the verses live in a particular database.
+struct Schema {
+    table: String,
+    book: String,
+    chapter: String,
+    verse: String,
+    text: String,
+}
+
+impl Store {
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .wrap_err_with(|| format!("cannot open {path:?}"))?;
+        let schema = Schema::detect(&conn)?
+            .ok_or_else(|| eyre!("{path:?} has no table of book, chapter, verse and text"))?;
+        let names = book_names(&conn, &schema)?;
+
+        let Schema {
+            table,
+            book,
+            chapter,
+            verse,
+            text,
+        } = &schema;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT DISTINCT \"{book}\", \"{chapter}\" FROM \"{table}\" ORDER BY 1, 2"
+        ))?;
+        let rows = stmt.query_map([], |r| Ok((r.get::<_, i64>(0)?, r.get::<_, i64>(1)?)))?;
+        let mut chapters = Vec::new();
+        for row in rows {
+            let (id, chapter) = row?;
+            chapters.push(Chapter {
+                book: names
+                    .get(&id)
+                    .cloned()
+                    .unwrap_or_else(|| format!("Book {id}")),
+                id,
+                chapter: chapter
+                    .try_into()
+                    .map_err(|_| eyre!("{path:?}: bad chapter {chapter}"))?,
+                verses: OnceLock::new(),
+            });
+        }
+        drop(stmt);
+
+        Ok(Self {
+            query: format!(
+                "SELECT \"{verse}\", \"{text}\" FROM \"{table}\" \
+                 WHERE \"{book}\" = ?1 AND \"{chapter}\" = ?2 ORDER BY 1"
+            ),
+            conn: Mutex::new(conn),
+            chapters,
+        })
+    }
+
+    pub fn chapters(&self) -> Vec<(String, u16)> {
+        self.chapters
+            .iter()
+            .map(|c| (c.book.clone(), c.chapter))
+            .collect()
+    }
+
+    /// The verses of a chapter, queried the first time it is asked for.
+    pub fn passage(&self, book: &str, chap: u16) -> Vec<&Verse> {
+        let Some(c) = self
+            .chapters
+            .iter()
+            .find(|c| c.book == book && c.chapter == chap)
+        else {
+            return Vec::new();
+        };
+        c.verses
+            .get_or_init(|| {
+                self.read_chapter(c).unwrap_or_else(|e| {
+                    tracing::error!("cannot read {book} {chap}: {e:?}");
+                    Vec::new()
+                })
+            })
+            .iter()
+            .collect()
+    }
+
+    fn read_chapter(&self, c: &Chapter) -> Result<Vec<Verse>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| eyre!("database lock poisoned"))?;
+        let mut stmt = conn.prepare_cached(&self.query)?;
+        let rows = stmt.query_map((c.id, c.chapter), |r| {
+            Ok((r.get::<_, i64>(0)?, r.get::<_, String>(1)?))
+        })?;
+        let mut out = Vec::new();
+        for row in rows {
+            let (verse, text) = row?;
+            out.push(Verse {
+                book: c.book.clone(),
+                chapter: c.chapter,
+                verse: verse.try_into()?,
+                text: strip_markup(&text),
+                ..Default::default()
+            });
+        }
+        Ok(out)
+    }
+}
+
+impl Schema {
+    /// Find the first table that has book, chapter, verse and text columns.
+    fn detect(conn: &Connection) -> Result<Option<Self>> {
+        for table in tables(conn)? {
+            let columns = columns(conn, &table)?;
+            let find = |candidates: &[&str]| {
+                columns
+                    .iter()
+                    .find(|c| candidates.contains(&c.to_ascii_lowercase().as_str()))
+                    .cloned()
+            };
+            if let (Some(book), Some(chapter), Some(verse), Some(text)) = (
+                find(&["book", "book_number", "book_id", "b"]),
+                find(&["chapter", "c"]),
+                find(&["verse", "v"]),
+                find(&["scripture", "text", "t"]),
+            ) {
+                return Ok(Some(Self {
+                    table,
+                    book,
+                    chapter,
+                    verse,
+                    text,
+                }));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Names for the numeric book ids of a database. MyBible ids are mapped
+/// through their own numbering; ids outside the canon fall back to a
+/// `*books` table when the database has one.
+fn book_names(conn: &Connection, schema: &Schema) -> Result<HashMap<i64, String>> {
+    let mybible = schema.book.eq_ignore_ascii_case("book_number");
+    let mut names: HashMap<i64, String> = canon::BOOKS
+        .iter()
+        .enumerate()
+        .map(|(i, b)| {
+            let id = if mybible {
+                MYBIBLE_NUMBERS[i]
+            } else {
+                i as i64 + 1
+            };
+            (id, b.name.to_owned())
+        })
+        .collect();
+
+    for table in tables(conn)? {
+        if !table.to_ascii_lowercase().ends_with("books") {
+            continue;
+        }
+        let columns = columns(conn, &table)?;
+        let find = |candidates: &[&str]| {
+            columns
+                .iter()
+                .find(|c| candidates.contains(&c.to_ascii_lowercase().as_str()))
+        };
+        let (Some(id), Some(name)) = (
+            find(&["book_number", "id", "book"]),
+            find(&["long_name", "name"]),
+        ) else {
+            continue;
+        };
+        let mut stmt = conn.prepare(&format!("SELECT \"{id}\", \"{name}\" FROM \"{table}\""))?;
+        let rows = stmt.query_map([], |r| Ok((r.get::<_, i64>(0)?, r.get::<_, String>(1)?)))?;
+        for row in rows {
+            let (id, name) = row?;
+            names.entry(id).or_insert(name);
+        }
+    }
+    Ok(names)
+}
+
+fn tables(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt =
+        conn.prepare("SELECT name FROM sqlite_master WHERE type = 'table' ORDER BY name")?;
+    let rows = stmt.query_map([], |r| r.get(0))?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+fn columns(conn: &Connection, table: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info(\"{table}\")"))?;
+    let rows = stmt.query_map([], |r| r.get(1))?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn database(name: &str, sql: &str) -> std::path::PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("logos-sqlite-{name}-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        Connection::open(&path).unwrap().execute_batch(sql).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_mysword() {
+        let path = database(
+            "mysword",
+            "CREATE TABLE Details (Title TEXT);
+             CREATE TABLE Bible (Book INT, Chapter INT, Verse INT, Scripture TEXT);
+             INSERT INTO Bible VALUES
+               (43, 11, 35, 'Jesus<WG2424> wept<WG1145>.'),
+               (1, 1, 2, 'And the earth<TS>x<Ts> was without form'),
+               (1, 1, 1, 'In the beginning<RF>note<Rf> God');",
+        );
+        let store = Store::open(&path).unwrap();
+        assert_eq!(
+            store.chapters(),
+            vec![("Genesis".into(), 1), ("John".into(), 11)]
+        );
+        let gen1 = store.passage("Genesis", 1);
+        assert_eq!(gen1[0].text, "In the beginning God");
+        assert_eq!(gen1[1].text, "And the earth was without form");
+        assert_eq!(store.passage("John", 11)[0].text, "Jesus wept.");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_mybible_numbers() {
+        let path = database(
+            "mybible",
+            "CREATE TABLE books (book_number NUMERIC, short_name TEXT, long_name TEXT);
+             INSERT INTO books VALUES (10, 'Gen', 'Genesis'), (345, 'Sus', 'Susanna');
+             CREATE TABLE verses (book_number NUMERIC, chapter NUMERIC, verse NUMERIC, text TEXT);
+             INSERT INTO verses VALUES
+               (730, 22, 21, 'The grace<S>5485</S> of our Lord'),
+               (345, 1, 1, 'There dwelt a man in Babylon');",
+        );
+        let store = Store::open(&path).unwrap();
+        assert_eq!(
+            store.chapters(),
+            vec![("Susanna".into(), 1), ("Revelation".into(), 22)]
+        );
+        assert_eq!(
+            store.passage("Revelation", 22)[0].text,
+            "The grace of our Lord"
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,455 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use flate2::read::ZlibDecoder;
+use tracing::warn;
+
+use super::{Bible, Verse, canon, strip_markup};
+
+/// A module described by a `mods.d/*.conf` file of a SWORD library.
+#[derive(Debug, Clone)]
+pub struct Module {
+    pub name: String,
+    pub description: String,
+    pub lang: String,
+    pub driver: String,
+    /// Raw `key=value` entries of the conf file.
+    pub conf: HashMap<String, String>,
+    /// The conf file the module was read from.
+    pub path: PathBuf,
+    root: PathBuf,
+}
+
+/// The SWORD library to use when none is configured: `$SWORD_PATH`, then
+/// `~/.sword`.
+pub fn default_dir() -> Option<PathBuf> {
+    std::env::var_os("SWORD_PATH")
+        .map(PathBuf::from)
+        .or_else(|| directories::BaseDirs::new().map(|d| d.home_dir().join(".sword")))
+}
+
+/// List the Bible text modules installed in a SWORD library directory,
+/// leaving out those whose versification cannot be read yet.
+pub fn modules(dir: &Path) -> Result<Vec<Module>> {
+    let confs = dir.join("mods.d");
+    let entries = fs::read_dir(&confs).wrap_err_with(|| format!("cannot open {confs:?}"))?;
+    let mut out = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|e| e == "conf") {
+            // One conf that cannot be read should not hide the rest.
+            match Module::from_conf(&path) {
+                Ok(module) if module.is_bible() && module.versification() != "KJV" => warn!(
+                    "skipping {}: versification {} is not supported yet",
+                    module.name,
+                    module.versification()
+                ),
+                Ok(module) if module.is_bible() => out.push(module),
+                Ok(_) => {}
+                Err(e) => warn!("skipping {path:?}: {e:#}"),
+            }
+        }
+    }
+    out.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(out)
+}
+
+impl Module {
+    /// Read a module's conf file; the library root is the parent of `mods.d`.
+    pub fn from_conf(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path).wrap_err_with(|| format!("cannot open {path:?}"))?;
+        let src = String::from_utf8_lossy(&bytes);
+        let root = path
+            .parent()
+            .and_then(Path::parent)
+            .ok_or_else(|| eyre!("{path:?} is not inside a mods.d directory"))?
+            .to_path_buf();
+
+        let mut name = None;
+        let mut conf = HashMap::new();
+        // A value whose line ends in `\` goes on over the next lines; it is
+        // kept here until its last line.
+        let mut continued: Option<(String, String)> = None;
+        for (lineno, line) in src.lines().enumerate() {
+            let line = line.trim();
+            let (line, more) = match line.strip_suffix('\\') {
+                Some(line) => (line.trim_end(), true),
+                None => (line, false),
+            };
+            if let Some((key, mut value)) = continued.take() {
+                value.push('\n');
+                value.push_str(line);
+                if more {
+                    continued = Some((key, value));
+                } else {
+                    conf.entry(key).or_insert(value);
+                }
+                continue;
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                name.get_or_insert_with(|| section.to_owned());
+            } else if let Some((key, value)) = line.split_once('=') {
+                let (key, value) = (key.trim().to_owned(), value.trim().to_owned());
+                if more {
+                    continued = Some((key, value));
+                } else {
+                    // Repeated keys (e.g. `GlobalOptionFilter`) keep the first value.
+                    conf.entry(key).or_insert(value);
+                }
+            } else {
+                return Err(eyre!("{path:?} line {}: expected key=value", lineno + 1));
+            }
+        }
+        if let Some((key, value)) = continued {
+            conf.entry(key).or_insert(value);
+        }
+        let name = name.ok_or_else(|| eyre!("{path:?}: missing [ModuleName] section"))?;
+        let get = |k: &str| conf.get(k).cloned().unwrap_or_default();
+        Ok(Self {
+            description: get("Description"),
+            lang: get("Lang"),
+            driver: get("ModDrv"),
+            name,
+            path: path.to_path_buf(),
+            root,
+            conf,
+        })
+    }
+
+    pub fn is_bible(&self) -> bool {
+        matches!(
+            self.driver.to_ascii_lowercase().as_str(),
+            "ztext" | "ztext4" | "rawtext" | "rawtext4"
+        )
+    }
+
+    /// The verse numbering scheme the module is keyed by; SWORD's default is
+    /// KJV.
+    pub fn versification(&self) -> &str {
+        self.conf.get("Versification").map_or("KJV", String::as_str)
+    }
+
+    fn data_dir(&self) -> PathBuf {
+        let rel = self.conf.get("DataPath").map_or("", String::as_str);
+        self.root.join(rel.trim_start_matches("./"))
+    }
+
+    /// Read every verse of the module into a [`Bible`].
+    pub fn load(&self) -> Result<Bible> {
+        if !self.is_bible() {
+            return Err(eyre!(
+                "{} is a {} module, not a Bible",
+                self.name,
+                self.driver
+            ));
+        }
+        if self.conf.get("CipherKey").is_some_and(|k| !k.is_empty()) {
+            return Err(eyre!("{} is encrypted, which is not supported", self.name));
+        }
+        let v11n = self.versification();
+        if v11n != "KJV" {
+            return Err(eyre!(
+                "{}: versification {v11n} is not supported yet",
+                self.name
+            ));
+        }
+
+        let latin1 = self
+            .conf
+            .get("Encoding")
+            .is_none_or(|e| !e.eq_ignore_ascii_case("UTF-8"));
+        let mut verses = Vec::new();
+        for (testament, books) in [("ot", 0..39), ("nt", 39..66)] {
+            let Some(reader) = self.open_testament(testament)? else {
+                continue;
+            };
+            for (key, idx) in verse_indexes(books) {
+                let raw = reader.entry(idx)?;
+                let text = if latin1 {
+                    raw.iter().map(|&b| b as char).collect()
+                } else {
+                    String::from_utf8_lossy(&raw).into_owned()
+                };
+                let text = strip_markup(&text);
+                if !text.is_empty() {
+                    let (book, chapter, verse) = key;
+                    verses.push(Verse {
+                        book: canon::BOOKS[book].name.to_owned(),
+                        chapter,
+                        verse,
+                        text,
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+        Ok(Bible {
+            verses,
+            ..Default::default()
+        })
+    }
+
+    fn open_testament(&self, testament: &str) -> Result<Option<Testament>> {
+        let dir = self.data_dir();
+        let wide = self.driver.ends_with('4');
+        let read = |ext: &str| -> Result<Option<Vec<u8>>> {
+            let path = dir.join(format!("{testament}{ext}"));
+            match fs::read(&path) {
+                Ok(bytes) => Ok(Some(bytes)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e).wrap_err_with(|| format!("cannot open {path:?}")),
+            }
+        };
+
+        if self.driver.to_ascii_lowercase().starts_with("ztext") {
+            let compress = self.conf.get("CompressType").map_or("ZIP", String::as_str);
+            if compress != "ZIP" {
+                return Err(eyre!(
+                    "{}: {compress} compression is not supported",
+                    self.name
+                ));
+            }
+            let (Some(blocks), Some(index), Some(data)) =
+                (read(".bzs")?, read(".bzv")?, read(".bzz")?)
+            else {
+                return Ok(None);
+            };
+            Ok(Some(Testament::Compressed {
+                blocks,
+                index,
+                data,
+                wide,
+                cache: None.into(),
+            }))
+        } else {
+            let (Some(index), Some(data)) = (read(".vss")?, read("")?) else {
+                return Ok(None);
+            };
+            Ok(Some(Testament::Raw { index, data, wide }))
+        }
+    }
+}
+
+/// The data files of one testament.
+enum Testament {
+    Raw {
+        index: Vec<u8>,
+        data: Vec<u8>,
+        wide: bool,
+    },
+    Compressed {
+        blocks: Vec<u8>,
+        index: Vec<u8>,
+        data: Vec<u8>,
+        wide: bool,
+        /// The last decompressed block; verses are read in order.
+        cache: std::cell::RefCell<Option<(u32, Vec<u8>)>>,
+    },
+}
+
+impl Testament {
+    /// The raw bytes stored at a verse index.
+    fn entry(&self, idx: usize) -> Result<Vec<u8>> {
+        match self {
+            Self::Raw { index, data, wide } => {
+                let width = if *wide { 8 } else { 6 };
+                let Some(rec) = index.get(idx * width..(idx + 1) * width) else {
+                    return Ok(Vec::new());
+                };
+                let start = u32_at(rec, 0) as usize;
+                let len = if *wide {
+                    u32_at(rec, 4) as usize
+                } else {
+                    u16_at(rec, 4) as usize
+                };
+                Ok(data.get(start..start + len).unwrap_or_default().to_vec())
+            }
+            Self::Compressed {
+                blocks,
+                index,
+                data,
+                wide,
+                cache,
+            } => {
+                let width = if *wide { 12 } else { 10 };
+                let Some(rec) = index.get(idx * width..(idx + 1) * width) else {
+                    return Ok(Vec::new());
+                };
+                let block = u32_at(rec, 0);
+                let start = u32_at(rec, 4) as usize;
+                let len = if *wide {
+                    u32_at(rec, 8) as usize
+                } else {
+                    u16_at(rec, 8) as usize
+                };
+                if len == 0 {
+                    return Ok(Vec::new());
+                }
+
+                let mut cache = cache.borrow_mut();
+                if cache.as_ref().is_none_or(|(b, _)| *b != block) {
+                    let b = block as usize * 12;
+                    let rec = blocks
+                        .get(b..b + 12)
+                        .ok_or_else(|| eyre!("verse {idx} points at missing block {block}"))?;
+                    let (offset, size) = (u32_at(rec, 0) as usize, u32_at(rec, 4) as usize);
+                    let compressed = data
+                        .get(offset..offset + size)
+                        .ok_or_else(|| eyre!("block {block} lies outside the data file"))?;
+                    let mut text = Vec::new();
+                    ZlibDecoder::new(compressed)
+                        .read_to_end(&mut text)
+                        .wrap_err_with(|| format!("cannot decompress block {block}"))?;
+                    *cache = Some((block, text));
+                }
+                let (_, text) = cache.as_ref().expect("block was just cached");
+                Ok(text.get(start..start + len).unwrap_or_default().to_vec())
+            }
+        }
+    }
+}
+
+fn u32_at(b: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([b[at], b[at + 1], b[at + 2], b[at + 3]])
+}
+
+fn u16_at(b: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([b[at], b[at + 1]])
+}
+
+/// Every verse of `books` with its index in the testament files.
+///
+/// Index 0 is the module heading and 1 the testament heading; each book and
+/// chapter then has a heading entry of its own before its verses.
+fn verse_indexes(books: std::ops::Range<usize>) -> Vec<((usize, u16, u16), usize)> {
+    let mut out = Vec::new();
+    let mut idx = 2;
+    for book in books {
+        idx += 1;
+        for (ch, &count) in canon::KJV_VERSES[book].iter().enumerate() {
+            idx += 1;
+            for v in 1..=count as u16 {
+                out.push(((book, ch as u16 + 1, v), idx));
+                idx += 1;
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{Compression, write::ZlibEncoder};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_verse_indexes() {
+        let ot = verse_indexes(0..39);
+        assert_eq!(ot[0], ((0, 1, 1), 4));
+        // Gen 2:1 follows the 31 verses of Gen 1 and the Gen 2 heading.
+        assert_eq!(ot[31], ((0, 2, 1), 36));
+        assert_eq!(ot.len(), 23145);
+        assert_eq!(verse_indexes(39..66).len(), 7957);
+    }
+
+    #[test]
+    fn test_load_ztext_module() {
+        let root = std::env::temp_dir().join(format!("logos-sword-{}", std::process::id()));
+        let data = root.join("modules/texts/ztext/test");
+        fs::create_dir_all(root.join("mods.d")).unwrap();
+        fs::create_dir_all(&data).unwrap();
+        fs::write(
+            root.join("mods.d/test.conf"),
+            "[Test]\nDataPath=./modules/texts/ztext/test/\nModDrv=zText\nEncoding=UTF-8\n\
+             Lang=en\nDescription=Test Bible\n",
+        )
+        .unwrap();
+
+        // One block holding Gen 1:1 and Gen 1:2.
+        let text = b"In the beginning.And the earth.";
+        let mut enc = ZlibEncoder::new(Vec::new(), Compression::default());
+        enc.write_all(text).unwrap();
+        let block = enc.finish().unwrap();
+        let mut bzs = Vec::new();
+        for n in [0, block.len() as u32, text.len() as u32] {
+            bzs.extend(n.to_le_bytes());
+        }
+        let mut bzv = vec![0u8; 4 * 10];
+        for (start, len) in [(0u32, 17u16), (17, 14)] {
+            bzv.extend(0u32.to_le_bytes());
+            bzv.extend(start.to_le_bytes());
+            bzv.extend(len.to_le_bytes());
+        }
+        fs::write(data.join("ot.bzs"), bzs).unwrap();
+        fs::write(data.join("ot.bzv"), bzv).unwrap();
+        fs::write(data.join("ot.bzz"), block).unwrap();
+
+        // A conf that cannot be read is skipped, not the whole library.
+        fs::write(root.join("mods.d/broken.conf"), "[Broken]\nnot a key\n").unwrap();
+        let modules = modules(&root).unwrap();
+        assert_eq!(modules.len(), 1);
+        assert_eq!(modules[0].description, "Test Bible");
+        let bible = modules[0].load().unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(bible.verses.len(), 2);
+        assert_eq!(bible.verses[1].book, "Genesis");
+        assert_eq!(bible.verses[1].verse, 2);
+        assert_eq!(bible.verses[1].text, "And the earth.");
+    }
+
+    #[test]
+    fn test_continued_values() {
+        let root = std::env::temp_dir().join(format!("logos-sword-conf-{}", std::process::id()));
+        fs::create_dir_all(root.join("mods.d")).unwrap();
+        let path = root.join("mods.d/kjv.conf");
+        fs::write(
+            &path,
+            "[KJV]\nModDrv=zText\nAbout=The King James Version \\\n\
+             of 1769, see \\\nhttps://www.crosswire.org/?a=b\nDescription=King James\n",
+        )
+        .unwrap();
+        let module = Module::from_conf(&path).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            module.conf["About"],
+            "The King James Version\nof 1769, see\nhttps://www.crosswire.org/?a=b"
+        );
+        assert_eq!(module.description, "King James");
+        assert!(module.is_bible());
+    }
+
+    #[test]
+    fn test_unsupported_versification() {
+        let root = std::env::temp_dir().join(format!("logos-sword-v11n-{}", std::process::id()));
+        fs::create_dir_all(root.join("mods.d")).unwrap();
+        fs::write(root.join("mods.d/kjv.conf"), "[KJV]\nModDrv=zText\n").unwrap();
+        fs::write(
+            root.join("mods.d/drb.conf"),
+            "[DRB]\nModDrv=zText\nVersification=Catholic\n",
+        )
+        .unwrap();
+        let modules = modules(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        let names: Vec<_> = modules.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["KJV"]);
+        assert_eq!(modules[0].versification(), "KJV");
+    }
+}
@@ -0,0 +1,400 @@
+use color_eyre::{Result, eyre::eyre};
+use tracing::warn;
+
+use super::{Bible, Break, BreakKind, Footnote, Verse, canon, line_col};
+
+/// Parse a USFM document (one or more books) into a [`Bible`].
+///
+/// Paragraph (`\p`, `\m`, `\pi`, …) and poetry (`\q1`…`\q4`) markers are kept
+/// as [`Break`]s on the verses, footnotes (`\f … \f*`) are collected into
+/// [`Bible::footnotes`], and headings, cross references and word-level
+/// attributes are dropped.
+pub fn parse(src: &str) -> Result<Bible> {
+    let mut builder = Builder::default();
+    // Paragraph-level markers whose content is not verse text (headings, ...).
+    let mut skip_line = false;
+    // The footnote being collected: caller, text, and whether we are inside `\fr`.
+    let mut note: Option<(String, String, bool)> = None;
+    let mut xref = false;
+    let mut pos = 0;
+
+    while pos < src.len() {
+        let rest = &src[pos..];
+        let at = |pos| {
+            let (ln, col) = line_col(src, pos);
+            move |e: String| eyre!("line {ln}, column {col}: {e}")
+        };
+
+        if !rest.starts_with('\\') {
+            let end = rest.find(['\\', '\n']).unwrap_or(rest.len());
+            let text = &rest[..end.max(1)];
+            if let Some((_, body, in_ref)) = &mut note {
+                if !*in_ref {
+                    push_words(body, text);
+                }
+            } else if !skip_line && !xref {
+                builder.push_text(text);
+            }
+            if text == "\n" {
+                skip_line = false;
+            }
+            pos += text.len();
+            continue;
+        }
+
+        let name_len = rest[1..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '+' || c == '*'))
+            .unwrap_or(rest.len() - 1);
+        let marker = &rest[1..1 + name_len];
+        let start = pos;
+        pos += 1 + name_len;
+        // A single space (or newline) terminates an opening marker.
+        if !marker.ends_with('*') && src[pos..].starts_with([' ', '\t', '\r']) {
+            pos += 1;
+        }
+        let marker = marker.trim_start_matches('+');
+
+        match marker {
+            "id" => {
+                let (code, n) = word(&src[pos..]);
+                if canon::by_usfm(code).is_none() {
+                    let (ln, col) = line_col(src, start);
+                    warn!("line {ln}, column {col}: book “{code}” is outside the canon");
+                }
+                builder.set_book(canon::usfm_name(code));
+                pos += n;
+                skip_line = true;
+            }
+            "c" => {
+                let (num, n) = word(&src[pos..]);
+                let chapter = num
+                    .parse()
+                    .map_err(|_| at(start)(format!("bad chapter “{num}”")))?;
+                builder.set_chapter(chapter).map_err(at(start))?;
+                pos += n;
+            }
+            "v" => {
+                let (num, n) = word(&src[pos..]);
+                // Bridged verses (`\v 1-2`) are stored under their first number.
+                let first = num.split(['-', ',']).next().unwrap_or_default();
+                let verse = first
+                    .parse()
+                    .map_err(|_| at(start)(format!("bad verse “{num}”")))?;
+                builder.start_verse(verse).map_err(at(start))?;
+                pos += n;
+                skip_line = false;
+            }
+            "f" | "fe" => {
+                let (caller, n) = word(&src[pos..]);
+                note = Some((caller.to_owned(), String::new(), false));
+                pos += n;
+            }
+            "f*" | "fe*" => {
+                let Some((caller, text, _)) = note.take() else {
+                    return Err(at(start)(format!("\\{marker} without an open footnote")));
+                };
+                builder.footnote(caller, text.trim().to_owned());
+            }
+            "fr" => {
+                if let Some((_, _, in_ref)) = &mut note {
+                    *in_ref = true;
+                }
+            }
+            m if note.is_some() && m.starts_with('f') => {
+                if let Some((_, _, in_ref)) = &mut note {
+                    *in_ref = false;
+                }
+            }
+            "x" => xref = true,
+            "x*" => xref = false,
+            m => {
+                if let Some(kind) = break_kind(m) {
+                    builder.paragraph(kind);
+                    skip_line = false;
+                } else if is_heading(m) {
+                    skip_line = true;
+                } else if m.ends_with('*') {
+                    // Closing a character style, e.g. `\w word|strong="H7225"\w*`:
+                    // drop the attributes of the span.
+                    builder.strip_attributes();
+                } else {
+                    builder.open_span();
+                }
+            }
+        }
+    }
+
+    if note.is_some() {
+        return Err(eyre!("end of document: footnote is never closed"));
+    }
+    Ok(builder.finish())
+}
+
+/// Map a paragraph style (USFM marker or USX `style`) to a layout break.
+pub(super) fn break_kind(style: &str) -> Option<BreakKind> {
+    match style {
+        "p" | "m" | "pi" | "pi1" | "pi2" | "pi3" | "pm" | "pmo" | "pmc" | "pmr" | "pc" | "mi"
+        | "nb" | "b" | "li" | "li1" | "li2" => Some(BreakKind::Paragraph),
+        "q" | "qm" => Some(BreakKind::Poetry(1)),
+        s if s.starts_with('q') && !s.starts_with("qa") && !s.starts_with("qs") => {
+            let level = s[1..].trim_start_matches(['m', 'r', 'c']).parse().ok()?;
+            Some(BreakKind::Poetry(level))
+        }
+        _ => None,
+    }
+}
+
+/// Paragraph styles whose content is not verse text.
+pub(super) fn is_heading(style: &str) -> bool {
+    let base = style.trim_end_matches(|c: char| c.is_ascii_digit());
+    matches!(
+        base,
+        "h" | "toc"
+            | "toca"
+            | "mt"
+            | "mte"
+            | "ms"
+            | "mr"
+            | "s"
+            | "sr"
+            | "r"
+            | "d"
+            | "sp"
+            | "cl"
+            | "cp"
+            | "cd"
+            | "ide"
+            | "rem"
+            | "sts"
+            | "usfm"
+            | "imt"
+            | "is"
+            | "ip"
+            | "io"
+            | "ili"
+    )
+}
+
+/// The next whitespace-delimited word and how many bytes it spans,
+/// including the whitespace after it.
+fn word(s: &str) -> (&str, usize) {
+    let trimmed = s.trim_start_matches([' ', '\t']);
+    let lead = s.len() - trimmed.len();
+    let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+    let mut n = lead + end;
+    if trimmed[end..].starts_with([' ', '\t']) {
+        n += 1;
+    }
+    (&trimmed[..end], n)
+}
+
+/// Append `text` to `buf`, collapsing runs of whitespace to one space.
+fn push_words(buf: &mut String, text: &str) {
+    if text.starts_with(char::is_whitespace) && !buf.is_empty() && !buf.ends_with(' ') {
+        buf.push(' ');
+    }
+    let mut words = text.split_whitespace().peekable();
+    while let Some(w) = words.next() {
+        buf.push_str(w);
+        if words.peek().is_some() {
+            buf.push(' ');
+        }
+    }
+    if text.ends_with(char::is_whitespace) && !text.trim().is_empty() {
+        buf.push(' ');
+    }
+}
+
+/// Accumulates books, chapters and verses for the USFM and USX loaders.
+#[derive(Default)]
+pub(super) struct Builder {
+    book: Option<String>,
+    chapter: Option<u16>,
+    verse: Option<Verse>,
+    pending: Option<BreakKind>,
+    span_start: usize,
+    verses: Vec<Verse>,
+    footnotes: Vec<Footnote>,
+}
+
+impl Builder {
+    pub fn set_book(&mut self, name: String) {
+        self.flush();
+        self.book = Some(name);
+        self.chapter = None;
+    }
+
+    pub fn set_chapter(&mut self, chapter: u16) -> Result<(), String> {
+        if self.book.is_none() {
+            return Err(format!("chapter {chapter} before the book id"));
+        }
+        self.flush();
+        self.chapter = Some(chapter);
+        Ok(())
+    }
+
+    pub fn start_verse(&mut self, verse: u16) -> Result<(), String> {
+        let (Some(book), Some(chapter)) = (self.book.clone(), self.chapter) else {
+            return Err(format!("verse {verse} before the first chapter"));
+        };
+        self.flush();
+        self.verse = Some(Verse {
+            book,
+            chapter,
+            verse,
+            ..Default::default()
+        });
+        Ok(())
+    }
+
+    pub fn paragraph(&mut self, kind: BreakKind) {
+        self.pending = Some(kind);
+    }
+
+    pub fn push_text(&mut self, text: &str) {
+        let Some(verse) = &mut self.verse else {
+            return;
+        };
+        if text.trim().is_empty() {
+            if !verse.text.is_empty() {
+                push_words(&mut verse.text, text);
+            }
+            return;
+        }
+        if let Some(kind) = self.pending.take() {
+            if !verse.text.is_empty() && !verse.text.ends_with(' ') {
+                verse.text.push(' ');
+            }
+            verse.breaks.push(Break {
+                offset: verse.text.len(),
+                kind,
+            });
+        }
+        push_words(&mut verse.text, text);
+    }
+
+    /// Remember where a character style span starts.
+    pub fn open_span(&mut self) {
+        self.span_start = self.verse.as_ref().map_or(0, |v| v.text.len());
+    }
+
+    /// Drop the `|key="value"` attribute list of the span that just closed.
+    pub fn strip_attributes(&mut self) {
+        if let Some(verse) = &mut self.verse
+            && let Some(bar) = verse.text.get(self.span_start..).and_then(|t| t.rfind('|'))
+        {
+            verse.text.truncate(self.span_start + bar);
+        }
+    }
+
+    pub fn footnote(&mut self, caller: String, text: String) {
+        if let Some(verse) = &self.verse {
+            self.footnotes.push(Footnote {
+                book: verse.book.clone(),
+                chapter: verse.chapter,
+                verse: verse.verse,
+                caller,
+                text,
+            });
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Some(mut verse) = self.verse.take() {
+            let len = verse.text.trim_end().len();
+            verse.text.truncate(len);
+            for b in &mut verse.breaks {
+                b.offset = b.offset.min(len);
+            }
+            self.verses.push(verse);
+        }
+    }
+
+    pub fn finish(mut self) -> Bible {
+        self.flush();
+        Bible {
+            verses: self.verses,
+            footnotes: self.footnotes,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const PSALM: &str = r#"\id PSA draft
+\h Psalms
+\c 23
+\d A Psalm of David.
+\q1 \v 1 The \nd Lord\nd* is my shepherd;
+\q2 I shall not want.\f + \fr 23:1 \ft Or \fq lack nothing\f*
+\p
+\v 2 He maketh me to lie down in \w green|strong="H1877"\w* pastures.
+"#;
+
+    #[test]
+    fn test_verses_and_markers() {
+        let bible = parse(PSALM).unwrap();
+        assert_eq!(bible.verses.len(), 2);
+
+        let v1 = &bible.verses[0];
+        assert_eq!((v1.book.as_str(), v1.chapter, v1.verse), ("Psalms", 23, 1));
+        assert_eq!(v1.text, "The Lord is my shepherd; I shall not want.");
+        assert_eq!(
+            v1.breaks,
+            vec![
+                Break {
+                    offset: 0,
+                    kind: BreakKind::Poetry(1)
+                },
+                Break {
+                    offset: 25,
+                    kind: BreakKind::Poetry(2)
+                },
+            ]
+        );
+
+        let v2 = &bible.verses[1];
+        assert_eq!(v2.text, "He maketh me to lie down in green pastures.");
+        assert_eq!(v2.breaks[0].kind, BreakKind::Paragraph);
+    }
+
+    #[test]
+    fn test_footnotes_are_separate() {
+        let bible = parse(PSALM).unwrap();
+        assert_eq!(
+            bible.footnotes,
+            vec![Footnote {
+                book: "Psalms".into(),
+                chapter: 23,
+                verse: 1,
+                caller: "+".into(),
+                text: "Or lack nothing".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_errors_carry_position() {
+        let err = parse("\\id GEN\n\\c 1\n\\v x In the beginning").unwrap_err();
+        assert_eq!(err.to_string(), "line 3, column 1: bad verse “x”");
+
+        let err = parse("\\id GEN\n\\v 1 In the beginning").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 1: verse 1 before the first chapter"
+        );
+    }
+
+    #[test]
+    fn test_books_outside_the_canon() {
+        let bible = parse("\\id TOB\n\\c 4\n\\v 15 What you hate, do to no one.\n").unwrap();
+        assert_eq!(bible.verses[0].book, "TOB");
+        assert_eq!(bible.verses[0].chapter, 4);
+        assert_eq!(bible.verses[0].text, "What you hate, do to no one.");
+    }
+}
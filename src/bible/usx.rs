@@ -0,0 +1,154 @@
+use color_eyre::{Result, eyre::eyre};
+use quick_xml::{Reader, events::Event};
+use tracing::warn;
+
+use super::{
+    Bible, attr, canon, line_col,
+    usfm::{Builder, break_kind, is_heading},
+};
+
+/// Parse a USX document into a [`Bible`], keeping the same paragraph, poetry
+/// and footnote information as the USFM loader.
+pub fn parse(src: &str) -> Result<Bible> {
+    let mut reader = Reader::from_str(src);
+    let mut builder = Builder::default();
+    // Depth of elements whose text must be skipped (headings, cross references).
+    let mut skip = 0usize;
+    // The footnote being collected: caller, text, and depth of a `fr` char inside it.
+    let mut note: Option<(String, String, usize)> = None;
+
+    loop {
+        let pos = reader.buffer_position() as usize;
+        let at = |e: String| {
+            let (ln, col) = line_col(src, pos);
+            eyre!("line {ln}, column {col}: {e}")
+        };
+        let event = reader.read_event().map_err(|e| {
+            let (ln, col) = line_col(src, reader.error_position() as usize);
+            eyre!("line {ln}, column {col}: {e}")
+        })?;
+        let is_start = matches!(event, Event::Start(_));
+        match event {
+            Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"book" => {
+                let code = attr(&e, b"code")?.unwrap_or_default();
+                if canon::by_usfm(&code).is_none() {
+                    let (ln, col) = line_col(src, pos);
+                    warn!("line {ln}, column {col}: book “{code}” is outside the canon");
+                }
+                builder.set_book(canon::usfm_name(&code));
+                // The element text is a description, not scripture.
+                if is_start {
+                    skip += 1;
+                }
+            }
+            Event::End(e) if e.name().as_ref() == b"book" => skip -= 1,
+            Event::Empty(e) if e.name().as_ref() == b"chapter" => {
+                if let Some(num) = attr(&e, b"number")? {
+                    let chapter = num
+                        .parse()
+                        .map_err(|_| at(format!("bad chapter “{num}”")))?;
+                    builder.set_chapter(chapter).map_err(at)?;
+                }
+            }
+            Event::Empty(e) if e.name().as_ref() == b"verse" => {
+                if let Some(num) = attr(&e, b"number")? {
+                    let first = num.split(['-', ',']).next().unwrap_or_default();
+                    let verse = first
+                        .parse()
+                        .map_err(|_| at(format!("bad verse “{num}”")))?;
+                    builder.start_verse(verse).map_err(at)?;
+                }
+            }
+            Event::Start(e) if e.name().as_ref() == b"para" => {
+                let style = attr(&e, b"style")?.unwrap_or_default();
+                if skip > 0 || is_heading(&style) {
+                    skip += 1;
+                } else if let Some(kind) = break_kind(&style) {
+                    builder.paragraph(kind);
+                }
+            }
+            Event::End(e) if e.name().as_ref() == b"para" && skip > 0 => skip -= 1,
+            Event::Start(e) if e.name().as_ref() == b"note" => {
+                let style = attr(&e, b"style")?.unwrap_or_default();
+                if style.starts_with('f') && skip == 0 {
+                    let caller = attr(&e, b"caller")?.unwrap_or_default();
+                    note = Some((caller, String::new(), 0));
+                } else {
+                    skip += 1;
+                }
+            }
+            Event::End(e) if e.name().as_ref() == b"note" => match note.take() {
+                Some((caller, text, _)) => builder.footnote(caller, text.trim().to_owned()),
+                None => skip = skip.saturating_sub(1),
+            },
+            Event::Start(e) if e.name().as_ref() == b"char" => {
+                if let Some((_, _, fr)) = &mut note
+                    && (*fr > 0 || attr(&e, b"style")?.as_deref() == Some("fr"))
+                {
+                    *fr += 1;
+                }
+            }
+            Event::End(e) if e.name().as_ref() == b"char" => {
+                if let Some((_, _, fr)) = &mut note {
+                    *fr = fr.saturating_sub(1);
+                }
+            }
+            Event::Text(t) => {
+                let text = t.unescape()?;
+                match &mut note {
+                    Some((_, body, 0)) => {
+                        body.push_str(&text);
+                    }
+                    Some(_) => {}
+                    None if skip == 0 => builder.push_text(&text),
+                    None => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    let mut bible = builder.finish();
+    for n in &mut bible.footnotes {
+        n.text = n.text.split_whitespace().collect::<Vec<_>>().join(" ");
+    }
+    Ok(bible)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::bible::BreakKind;
+
+    #[test]
+    fn test_usx() {
+        let bible = parse(
+            r#"<usx version="3.0">
+  <book code="JHN" style="id">draft</book>
+  <chapter number="1" style="c" sid="JHN 1"/>
+  <para style="s1">The Word</para>
+  <para style="p"><verse number="1" style="v" sid="JHN 1:1"/>In the beginning was the
+  <char style="w">Word</char>.<note caller="+" style="f"><char style="fr">1:1 </char><char style="ft">Or message</char></note><verse eid="JHN 1:1"/></para>
+  <para style="q1"><verse number="2" style="v"/>He was with God.</para>
+</usx>"#,
+        )
+        .unwrap();
+        assert_eq!(bible.verses.len(), 2);
+        assert_eq!(bible.verses[0].book, "John");
+        assert_eq!(bible.verses[0].text, "In the beginning was the Word.");
+        assert_eq!(bible.verses[0].breaks[0].kind, BreakKind::Paragraph);
+        assert_eq!(bible.verses[1].breaks[0].kind, BreakKind::Poetry(1));
+        assert_eq!(bible.footnotes[0].text, "Or message");
+        assert_eq!(bible.footnotes[0].caller, "+");
+
+        let bible = parse(
+            r#"<usx version="3.0"><book code="SIR" style="id"/><chapter number="1" style="c"/>
+  <para style="p"><verse number="1" style="v"/>All wisdom is from the Lord.</para></usx>"#,
+        )
+        .unwrap();
+        assert_eq!(bible.verses[0].book, "SIR");
+    }
+}
@@ -0,0 +1,136 @@
+use color_eyre::{Result, eyre::eyre};
+use quick_xml::{Reader, events::Event};
+
+use super::{Bible, Verse, attr, canon, line_col};
+
+/// Parse a Zefania XML document
+/// (`<XMLBIBLE><BIBLEBOOK bnumber><CHAPTER cnumber><VERS vnumber>`) into a
+/// [`Bible`]. Books are named after the canon table when `bnumber` is one of
+/// the 66 books, otherwise after `bname`.
+pub fn parse(src: &str) -> Result<Bible> {
+    let mut reader = Reader::from_str(src);
+    let mut out = Vec::new();
+    let mut book: Option<String> = None;
+    let mut chapter: Option<u16> = None;
+    let mut verse: Option<Verse> = None;
+    // Depth of `<NOTE>`/`<XREF>` elements whose text is not verse text.
+    let mut skip = 0usize;
+
+    loop {
+        let pos = reader.buffer_position() as usize;
+        let at = |e: String| {
+            let (ln, col) = line_col(src, pos);
+            eyre!("line {ln}, column {col}: {e}")
+        };
+        let event = reader.read_event().map_err(|e| {
+            let (ln, col) = line_col(src, reader.error_position() as usize);
+            eyre!("line {ln}, column {col}: {e}")
+        })?;
+        match event {
+            Event::Start(e) => match e.name().as_ref() {
+                b"BIBLEBOOK" => {
+                    let number = attr(&e, b"bnumber")?.unwrap_or_default();
+                    let canonical = number
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|n| canon::BOOKS.get(n.wrapping_sub(1)));
+                    book = match (canonical, attr(&e, b"bname")?) {
+                        (Some(b), _) => Some(b.name.to_owned()),
+                        (None, Some(name)) => Some(name),
+                        (None, None) => return Err(at(format!("bad book number “{number}”"))),
+                    };
+                }
+                b"CHAPTER" => {
+                    let number = attr(&e, b"cnumber")?.unwrap_or_default();
+                    chapter = Some(
+                        number
+                            .parse()
+                            .map_err(|_| at(format!("bad chapter “{number}”")))?,
+                    );
+                }
+                b"VERS" => {
+                    let (Some(book), Some(chapter)) = (&book, chapter) else {
+                        return Err(at("<VERS> outside of a chapter".into()));
+                    };
+                    let number = attr(&e, b"vnumber")?.unwrap_or_default();
+                    verse = Some(Verse {
+                        book: book.clone(),
+                        chapter,
+                        verse: number
+                            .parse()
+                            .map_err(|_| at(format!("bad verse “{number}”")))?,
+                        ..Default::default()
+                    });
+                }
+                b"NOTE" | b"XREF" => skip += 1,
+                _ => {}
+            },
+            Event::End(e) => match e.name().as_ref() {
+                b"VERS" => {
+                    if let Some(mut v) = verse.take() {
+                        v.text = v.text.split_whitespace().collect::<Vec<_>>().join(" ");
+                        out.push(v);
+                    }
+                }
+                b"NOTE" | b"XREF" => skip = skip.saturating_sub(1),
+                _ => {}
+            },
+            Event::Empty(e) if e.name().as_ref() == b"BR" => {
+                if let Some(v) = &mut verse {
+                    v.text.push(' ');
+                }
+            }
+            Event::Text(t) if skip == 0 => {
+                if let Some(v) = &mut verse {
+                    v.text.push_str(&t.unescape()?);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(Bible {
+        verses: out,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_zefania() {
+        let bible = parse(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<XMLBIBLE biblename="Reina-Valera">
+  <BIBLEBOOK bnumber="43" bname="Juan">
+    <CHAPTER cnumber="3">
+      <VERS vnumber="16">Porque de tal manera amó Dios al mundo,<NOTE>nota</NOTE>
+        que ha dado a su Hijo unigénito</VERS>
+    </CHAPTER>
+  </BIBLEBOOK>
+</XMLBIBLE>"#,
+        )
+        .unwrap();
+        assert_eq!(bible.verses.len(), 1);
+        let v = &bible.verses[0];
+        assert_eq!((v.book.as_str(), v.chapter, v.verse), ("John", 3, 16));
+        assert_eq!(
+            v.text,
+            "Porque de tal manera amó Dios al mundo, que ha dado a su Hijo unigénito"
+        );
+    }
+
+    #[test]
+    fn test_bad_verse_number() {
+        let err = parse(
+            "<XMLBIBLE><BIBLEBOOK bnumber=\"1\"><CHAPTER cnumber=\"1\">\n<VERS vnumber=\"a\">",
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 1: bad verse “a”");
+    }
+}
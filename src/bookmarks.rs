@@ -0,0 +1,190 @@
+use std::path::{Path, PathBuf};
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+use crate::{bible::canon, store};
+
+/// A saved place. Verses are named by book and number, so a bookmark holds
+/// whichever translation is open.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub book: String,
+    pub chapter: u16,
+    pub verse: u16,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub label: String,
+    /// Folder it is filed under; empty for none.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub folder: String,
+    /// When it was made, in seconds since the Unix epoch.
+    #[serde(default)]
+    pub created: u64,
+}
+
+impl Bookmark {
+    pub fn new(book: String, chapter: u16, verse: u16) -> Self {
+        Self {
+            book,
+            chapter,
+            verse,
+            created: store::now(),
+            ..Default::default()
+        }
+    }
+
+    pub fn is_at(&self, book: &str, chapter: u16, verse: u16) -> bool {
+        self.book == book && self.chapter == chapter && self.verse == verse
+    }
+
+    /// Position in bible order; books outside the canon come last.
+    fn position(&self) -> (usize, &str, u16, u16) {
+        (
+            canon::order(&self.book),
+            &self.book,
+            self.chapter,
+            self.verse,
+        )
+    }
+}
+
+/// Orders the bookmark list can be shown in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Sort {
+    #[default]
+    Reference,
+    Label,
+    Folder,
+    Newest,
+}
+
+impl Sort {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Reference => Self::Label,
+            Self::Label => Self::Folder,
+            Self::Folder => Self::Newest,
+            Self::Newest => Self::Reference,
+        }
+    }
+}
+
+/// Every bookmark, at most one per verse.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bookmarks {
+    pub list: Vec<Bookmark>,
+}
+
+impl Bookmarks {
+    /// Read the saved bookmarks; none yet is not an error.
+    pub fn load() -> Result<Self> {
+        Self::load_from(&path())
+    }
+
+    fn load_from(path: &Path) -> Result<Self> {
+        store::load(path)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&path())
+    }
+
+    fn save_to(&self, path: &Path) -> Result<()> {
+        store::save(path, self)
+    }
+
+    pub fn get(&self, book: &str, chapter: u16, verse: u16) -> Option<&Bookmark> {
+        self.list.iter().find(|b| b.is_at(book, chapter, verse))
+    }
+
+    /// Add a bookmark, replacing the one on the same verse.
+    pub fn set(&mut self, bookmark: Bookmark) {
+        match self
+            .list
+            .iter_mut()
+            .find(|b| b.is_at(&bookmark.book, bookmark.chapter, bookmark.verse))
+        {
+            Some(b) => *b = bookmark,
+            None => self.list.push(bookmark),
+        }
+    }
+
+    pub fn remove(&mut self, book: &str, chapter: u16, verse: u16) {
+        self.list.retain(|b| !b.is_at(book, chapter, verse));
+    }
+
+    /// The bookmarks in `sort` order; ties keep bible order.
+    pub fn sorted(&self, sort: Sort) -> Vec<&Bookmark> {
+        let mut list: Vec<&Bookmark> = self.list.iter().collect();
+        list.sort_by(|a, b| a.position().cmp(&b.position()));
+        match sort {
+            Sort::Reference => {}
+            Sort::Label => list.sort_by_key(|b| (b.label.is_empty(), b.label.to_lowercase())),
+            Sort::Folder => list.sort_by_key(|b| (b.folder.is_empty(), b.folder.to_lowercase())),
+            Sort::Newest => list.sort_by_key(|b| std::cmp::Reverse(b.created)),
+        }
+        list
+    }
+}
+
+fn path() -> PathBuf {
+    store::path("bookmarks.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_bookmarks() {
+        let bookmark = |book: &str, chapter, label: &str, created| Bookmark {
+            book: book.into(),
+            chapter,
+            verse: 1,
+            label: label.into(),
+            created,
+            ..Default::default()
+        };
+        let mut bookmarks = Bookmarks::default();
+        bookmarks.set(bookmark("John", 3, "", 1));
+        bookmarks.set(bookmark("Genesis", 1, "creation", 2));
+        bookmarks.set(bookmark("Psalms", 23, "shepherd", 3));
+        bookmarks.set(bookmark("John", 3, "love", 4));
+        assert_eq!(bookmarks.list.len(), 3);
+        assert_eq!(bookmarks.get("John", 3, 1).unwrap().label, "love");
+
+        fn order(bookmarks: &Bookmarks, sort: Sort) -> Vec<&str> {
+            bookmarks
+                .sorted(sort)
+                .into_iter()
+                .map(|b| b.book.as_str())
+                .collect()
+        }
+        assert_eq!(
+            order(&bookmarks, Sort::Reference),
+            ["Genesis", "Psalms", "John"]
+        );
+        assert_eq!(
+            order(&bookmarks, Sort::Label),
+            ["Genesis", "John", "Psalms"]
+        );
+        assert_eq!(
+            order(&bookmarks, Sort::Newest),
+            ["John", "Psalms", "Genesis"]
+        );
+
+        let path =
+            std::env::temp_dir().join(format!("logos-bookmarks-{}.json", std::process::id()));
+        bookmarks.save_to(&path).unwrap();
+        assert_eq!(Bookmarks::load_from(&path).unwrap(), bookmarks);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(Bookmarks::load_from(&path).unwrap(), Bookmarks::default());
+
+        bookmarks.remove("Psalms", 23, 1);
+        assert_eq!(order(&bookmarks, Sort::Reference), ["Genesis", "John"]);
+    }
+}
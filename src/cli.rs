@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use clap::Parser;
 
 use crate::config::{get_config_dir, get_data_dir};
@@ -12,6 +14,22 @@ pub struct Cli {
     /// Frame rate, i.e. number of frames per second
     #[arg(short, long, value_name = "FLOAT", default_value_t = 60.0)]
     pub frame_rate: f64,
+
+    /// Abbreviation of the translation to open, e.g. KJV
+    #[arg(long, value_name = "ABBREV")]
+    pub translation: Option<String>,
+
+    /// Print a passage, e.g. "Jn 3:16-18; Rom 8:1,28", and exit
+    #[arg(long, value_name = "REFERENCE")]
+    pub print: Option<String>,
+
+    /// Import a reading plan from a JSON or CSV list of references, and exit
+    #[arg(long, value_name = "FILE")]
+    pub import_plan: Option<PathBuf>,
+
+    /// List the Bible modules of the SWORD library and exit
+    #[arg(long)]
+    pub list_modules: bool,
 }
 
 const VERSION_MESSAGE: &str = concat!(
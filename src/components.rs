@@ -1,16 +1,174 @@
+use std::sync::Arc;
+
 use color_eyre::Result;
 use crossterm::event::{KeyEvent, MouseEvent};
 use ratatui::{
     Frame,
-    layout::{Rect, Size},
+    layout::{Constraint, Flex, Layout, Rect, Size},
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
 };
 use tokio::sync::mpsc::UnboundedSender;
 
-use crate::{action::Action, app::Focus, config::Config, tui::Event};
+use crate::{
+    action::Action, app::Focus, bible::Bible, bookmarks::Bookmarks, config::Config,
+    highlights::Highlights, library::Translation, notes::Notes, plans::Plans, tags::Tags,
+    tui::Event,
+};
 
+pub mod bookmarks;
+pub mod concordance;
+pub mod differences;
+pub mod editor;
 pub mod fps;
+pub mod goto;
+pub mod highlights;
 pub mod index;
+pub mod notes;
+pub mod picker;
+pub mod plans;
 pub mod reader;
+pub mod search;
+pub mod tags;
+
+/// A `width` × `height` rectangle centred in `area`, for popups.
+pub fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+/// A bordered pane of a popup, green while it has the keys.
+pub fn pane<'a>(active: bool) -> Block<'a> {
+    let color = if active {
+        Color::Green
+    } else {
+        Color::DarkGray
+    };
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(color))
+}
+
+/// The line a query is typed into, with a cursor while `editing` and
+/// `message` after it, such as why nothing was found.
+pub fn query_line<'a>(
+    f: &mut Frame,
+    area: Rect,
+    title: impl Into<Line<'a>>,
+    input: &'a str,
+    editing: bool,
+    message: Option<Span<'a>>,
+) {
+    let mut line = vec![Span::raw(input)];
+    if editing {
+        line.push(Span::raw(" ").reversed());
+    }
+    line.extend(message.map(|m| Span::styled(format!("  {}", m.content), m.style)));
+    f.render_widget(
+        Paragraph::new(Line::from(line)).block(pane(editing).title(title)),
+        area,
+    );
+}
+
+/// A list over most of `area`, with `hint` on its bottom border and the
+/// `selected` item reversed, as the bookmarks, tags and plans show.
+pub fn list_popup<'a>(
+    f: &mut Frame,
+    area: Rect,
+    title: impl Into<Line<'a>>,
+    hint: Line<'a>,
+    items: Vec<ListItem<'a>>,
+    selected: usize,
+    state: &mut ListState,
+) {
+    let popup = centered(area, area.width * 4 / 5, area.height * 4 / 5);
+    f.render_widget(Clear, popup);
+    state.select(Some(selected));
+    f.render_stateful_widget(
+        List::new(items)
+            .block(
+                Block::default()
+                    .title(title)
+                    .title_bottom(hint)
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Green)),
+            )
+            .highlight_style(Style::default().reversed()),
+        popup,
+        state,
+    );
+}
+
+/// A query line over a list of hits in bible order, with each book that has
+/// hits and how many beside it, as search and the concordance show them.
+pub struct HitList<'a> {
+    pub title: String,
+    pub input: &'a str,
+    /// Whether keys go to the query line rather than the hits.
+    pub editing: bool,
+    /// Shown after the query, such as why nothing was found.
+    pub message: Option<Span<'a>>,
+    pub books: &'a [(String, usize)],
+    /// Book of the selected hit.
+    pub current: Option<&'a str>,
+    pub hits_title: String,
+    pub hits: Vec<ListItem<'a>>,
+}
+
+impl HitList<'_> {
+    pub fn render(self, f: &mut Frame, area: Rect, state: &mut ListState) {
+        let popup = centered(area, area.width * 9 / 10, area.height * 9 / 10);
+        f.render_widget(Clear, popup);
+        let [prompt, body] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(popup);
+        let [books, hits] =
+            Layout::horizontal([Constraint::Length(24), Constraint::Min(0)]).areas(body);
+        query_line(
+            f,
+            prompt,
+            self.title,
+            self.input,
+            self.editing,
+            self.message,
+        );
+
+        let total = self.hits.len();
+        let items: Vec<ListItem> = self
+            .books
+            .iter()
+            .map(|(book, n)| {
+                let item = ListItem::new(format!("{book:<16}{n:>5}"));
+                if Some(book.as_str()) == self.current {
+                    item.style(Style::default().bold())
+                } else {
+                    item
+                }
+            })
+            .collect();
+        f.render_widget(
+            List::new(items).block(
+                pane(false)
+                    .title("Books")
+                    .title_bottom(format!("total {total}")),
+            ),
+            books,
+        );
+
+        f.render_stateful_widget(
+            List::new(self.hits)
+                .block(pane(!self.editing).title(self.hits_title))
+                .highlight_style(Style::default().reversed()),
+            hits,
+            state,
+        );
+    }
+}
 
 pub trait AsAny {
     fn as_any(&mut self) -> &mut dyn std::any::Any;
@@ -52,6 +210,103 @@ pub trait Component: AsAny {
         let _ = config; // to appease clippy
         Ok(())
     }
+    /// Register the bible being read, at startup and whenever the translation changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `bible` - The text of the translation.
+    /// * `translation` - The library entry it was loaded from.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - An Ok result or an error.
+    fn register_bible_handler(
+        &mut self,
+        bible: Arc<Bible>,
+        translation: &Translation,
+    ) -> Result<()> {
+        let _ = (bible, translation); // to appease clippy
+        Ok(())
+    }
+    /// Register the translations shown side by side with the main one.
+    ///
+    /// # Arguments
+    ///
+    /// * `columns` - The extra translations, in column order.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - An Ok result or an error.
+    fn register_parallel_handler(&mut self, columns: &[(Translation, Arc<Bible>)]) -> Result<()> {
+        let _ = columns; // to appease clippy
+        Ok(())
+    }
+    /// Register the saved bookmarks, at startup and whenever they change.
+    ///
+    /// # Arguments
+    ///
+    /// * `bookmarks` - Every bookmark.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - An Ok result or an error.
+    fn register_bookmarks_handler(&mut self, bookmarks: &Bookmarks) -> Result<()> {
+        let _ = bookmarks; // to appease clippy
+        Ok(())
+    }
+    /// Register the saved highlights, at startup and whenever they change.
+    ///
+    /// # Arguments
+    ///
+    /// * `highlights` - Every highlight, of every translation.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - An Ok result or an error.
+    fn register_highlights_handler(&mut self, highlights: &Highlights) -> Result<()> {
+        let _ = highlights; // to appease clippy
+        Ok(())
+    }
+    /// Register the notes, at startup and whenever they change.
+    ///
+    /// # Arguments
+    ///
+    /// * `notes` - Every note.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - An Ok result or an error.
+    fn register_notes_handler(&mut self, notes: &Notes) -> Result<()> {
+        let _ = notes; // to appease clippy
+        Ok(())
+    }
+    /// Register the tags, at startup and whenever they change.
+    ///
+    /// # Arguments
+    ///
+    /// * `tags` - Every tag on every verse.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - An Ok result or an error.
+    fn register_tags_handler(&mut self, tags: &Tags) -> Result<()> {
+        let _ = tags; // to appease clippy
+        Ok(())
+    }
+    /// Register the reading plans and the progress through them, at startup
+    /// and whenever it changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `plans` - Every plan, and those started.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - An Ok result or an error.
+    fn register_plans_handler(&mut self, plans: &Plans) -> Result<()> {
+        let _ = plans; // to appease clippy
+        Ok(())
+    }
     /// Initialize the component with a specified area if necessary.
     ///
     /// # Arguments
@@ -95,6 +350,15 @@ pub trait Component: AsAny {
         let _ = key; // to appease clippy
         Ok(None)
     }
+    /// Whether the component is taking typed text, such as a prompt, so that
+    /// keys go to it rather than to the global key bindings.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether keys are being typed into the component.
+    fn captures_input(&self) -> bool {
+        false
+    }
     /// Handle mouse events and produce actions if necessary.
     ///
     /// # Arguments
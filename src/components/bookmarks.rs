@@ -0,0 +1,205 @@
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    action::Action,
+    app::Focus,
+    bookmarks::{Bookmark, Bookmarks, Sort},
+    components::{Component, list_popup},
+};
+
+/// Which field of the selected bookmark is being edited.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Label,
+    Folder,
+}
+
+/// The saved bookmarks, to jump to, rename, file in folders or delete.
+pub struct BookmarkList {
+    bookmarks: Bookmarks,
+    sort: Sort,
+    /// The bookmarks in `sort` order.
+    shown: Vec<Bookmark>,
+    selected: usize,
+    list_state: ListState,
+    editing: Option<(Field, String)>,
+    error: Option<String>,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl BookmarkList {
+    pub fn new() -> Self {
+        Self {
+            bookmarks: Bookmarks::default(),
+            sort: Sort::default(),
+            shown: Vec::new(),
+            selected: 0,
+            list_state: ListState::default(),
+            editing: None,
+            error: None,
+            action_tx: None,
+        }
+    }
+
+    fn sort(&mut self) {
+        self.shown = self
+            .bookmarks
+            .sorted(self.sort)
+            .into_iter()
+            .cloned()
+            .collect();
+        self.selected = self.selected.min(self.shown.len().saturating_sub(1));
+    }
+
+    fn edit_key(&mut self, key: crossterm::event::KeyEvent) -> Option<Action> {
+        use crossterm::event::KeyCode::*;
+        let (field, input) = self.editing.as_mut()?;
+        match key.code {
+            Esc => self.editing = None,
+            Enter => {
+                let mut bookmark = self.shown.get(self.selected)?.clone();
+                match field {
+                    Field::Label => bookmark.label = input.trim().to_owned(),
+                    Field::Folder => bookmark.folder = input.trim().to_owned(),
+                }
+                self.editing = None;
+                return Some(Action::SaveBookmark(bookmark));
+            }
+            Backspace => {
+                input.pop();
+            }
+            Char(c) => input.push(c),
+            _ => {}
+        }
+        None
+    }
+}
+
+impl Component for BookmarkList {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_bookmarks_handler(&mut self, bookmarks: &Bookmarks) -> Result<()> {
+        self.bookmarks = bookmarks.clone();
+        self.sort();
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Bookmarks => {
+                self.editing = None;
+                self.error = None;
+            }
+            Action::Error(e) => self.error = Some(e),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_key_event(&mut self, key: crossterm::event::KeyEvent) -> Result<Option<Action>> {
+        use crossterm::event::KeyCode::*;
+        if self.editing.is_some() {
+            return Ok(self.edit_key(key));
+        }
+        self.error = None;
+        let selected = self.shown.get(self.selected);
+        match key.code {
+            Up | Char('k') => self.selected = self.selected.saturating_sub(1),
+            Down | Char('j') if self.selected + 1 < self.shown.len() => self.selected += 1,
+            Char('s') => {
+                self.sort = self.sort.next();
+                self.sort();
+            }
+            Char('r') => {
+                if let Some(b) = selected {
+                    self.editing = Some((Field::Label, b.label.clone()));
+                }
+            }
+            Char('f') => {
+                if let Some(b) = selected {
+                    self.editing = Some((Field::Folder, b.folder.clone()));
+                }
+            }
+            Char('d') | Delete => {
+                if let Some(b) = selected {
+                    return Ok(Some(Action::DeleteBookmark {
+                        book: b.book.clone(),
+                        chapter: b.chapter,
+                        verse: b.verse,
+                    }));
+                }
+            }
+            Enter => {
+                if let (Some(b), Some(tx)) = (selected, &self.action_tx) {
+                    tx.send(Action::OpenPassage {
+                        book: b.book.clone(),
+                        chapter: b.chapter,
+                    })?;
+                    tx.send(Action::SelectVerse {
+                        verse: b.verse,
+                        end: None,
+                    })?;
+                    return Ok(Some(Action::ClosePopup));
+                }
+            }
+            Esc | Char('q') => return Ok(Some(Action::ClosePopup)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame, area: Rect, focus: Focus) -> Result<()> {
+        if focus != Focus::Bookmarks {
+            return Ok(());
+        }
+        let items: Vec<ListItem> = self
+            .shown
+            .iter()
+            .map(|b| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{:<16}", b.folder), Style::default().dim()),
+                    Span::raw(format!(
+                        "{:<24}",
+                        format!("{} {}:{}", b.book, b.chapter, b.verse)
+                    )),
+                    Span::raw(b.label.as_str()),
+                ]))
+            })
+            .collect();
+
+        let bottom = match (&self.editing, &self.error) {
+            (Some((field, input)), _) => Line::from(vec![
+                Span::raw(match field {
+                    Field::Label => "label: ",
+                    Field::Folder => "folder: ",
+                }),
+                Span::raw(input.as_str()),
+                Span::raw(" ").reversed(),
+            ]),
+            (None, Some(e)) => Line::from(e.as_str()).red(),
+            (None, None) => {
+                Line::from("Enter jump · r rename · f folder · d delete · s sort").dim()
+            }
+        };
+        let title = if self.shown.is_empty() {
+            "Bookmarks · none yet, b in the reader adds one".to_owned()
+        } else {
+            format!("Bookmarks · by {}", self.sort)
+        };
+        list_popup(
+            f,
+            area,
+            title,
+            bottom,
+            items,
+            self.selected,
+            &mut self.list_state,
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,396 @@
+use std::{fs, path::PathBuf, sync::Arc};
+
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    action::Action,
+    app::Focus,
+    bible::{
+        Bible,
+        search::{self, Mode},
+    },
+    components::{Component, HitList},
+    config::get_data_dir,
+    library::Translation,
+};
+
+/// Characters of context shown before each occurrence.
+const CONTEXT: usize = 40;
+
+/// One occurrence, with the text around it.
+struct Occurrence {
+    book: String,
+    chapter: u16,
+    verse: u16,
+    before: String,
+    word: String,
+    after: String,
+}
+
+/// Key-word-in-context list of every occurrence of one word, with how often
+/// each book uses it.
+pub struct Concordance {
+    bible: Arc<Bible>,
+    input: String,
+    /// The word the list was built for.
+    word: String,
+    editing: bool,
+    status: Option<Result<String, String>>,
+    lines: Vec<Occurrence>,
+    /// Each book with occurrences and how many, in bible order.
+    books: Vec<(String, usize)>,
+    selected: usize,
+    list_state: ListState,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl Concordance {
+    pub fn new() -> Self {
+        Self {
+            bible: Arc::default(),
+            input: String::new(),
+            word: String::new(),
+            editing: true,
+            status: None,
+            lines: Vec::new(),
+            books: Vec::new(),
+            selected: 0,
+            list_state: ListState::default(),
+            action_tx: None,
+        }
+    }
+
+    /// List the occurrences of the typed word. A word typed without accents
+    /// also finds its accented spellings.
+    fn build(&mut self) {
+        self.lines.clear();
+        self.books.clear();
+        self.selected = 0;
+        self.status = None;
+        self.word = self.input.trim().to_lowercase();
+        let folded = search::fold(&self.word) == self.word;
+        let index = self.bible.search_index();
+        let (mut at, mut words, mut text) = (None, Vec::new(), String::new());
+        for (id, pos) in index.occurrences(&self.word, folded) {
+            let (book, chapter, verse) = index.verse(id);
+            if at != Some(id) {
+                at = Some(id);
+                text = self
+                    .bible
+                    .passage(book, chapter)
+                    .into_iter()
+                    .find(|v| v.verse == verse)
+                    .map_or_else(String::new, |v| v.text.clone());
+                words = search::words(&text);
+            }
+            let Some((range, _)) = words.get(pos as usize) else {
+                continue;
+            };
+            let before = &text[..range.start];
+            let skip = before.chars().count().saturating_sub(CONTEXT);
+            match self.books.last_mut() {
+                Some((b, n)) if b == book => *n += 1,
+                _ => self.books.push((book.to_owned(), 1)),
+            }
+            self.lines.push(Occurrence {
+                book: book.to_owned(),
+                chapter,
+                verse,
+                before: before.chars().skip(skip).collect(),
+                word: text[range.clone()].to_owned(),
+                after: text[range.end..].to_owned(),
+            });
+        }
+        if self.lines.is_empty() {
+            self.status = Some(Err(format!("“{}” does not occur", self.word)));
+        }
+    }
+
+    /// Write the list to a text file in the data directory, one tab-separated
+    /// occurrence per line.
+    fn export(&self) -> Result<PathBuf> {
+        let name = file_name(&self.word);
+        if name.is_empty() || self.lines.is_empty() {
+            return Err(eyre!("nothing to export"));
+        }
+        let dir = get_data_dir().join("concordances");
+        fs::create_dir_all(&dir).wrap_err_with(|| format!("cannot create {dir:?}"))?;
+        let path = dir.join(format!("{name}.tsv"));
+        let mut out = String::new();
+        for l in &self.lines {
+            out.push_str(&format!(
+                "{} {}:{}\t{}\t{}\t{}\n",
+                l.book,
+                l.chapter,
+                l.verse,
+                l.before.trim_start(),
+                l.word,
+                l.after.trim_end()
+            ));
+        }
+        for (book, n) in &self.books {
+            out.push_str(&format!("# {book}\t{n}\n"));
+        }
+        out.push_str(&format!("# total\t{}\n", self.lines.len()));
+        fs::write(&path, out).wrap_err_with(|| format!("cannot write {path:?}"))?;
+        Ok(path)
+    }
+
+    /// Move the selection to the first occurrence in the next book, or in
+    /// the previous one going back.
+    fn skip_book(&mut self, forward: bool) {
+        let Some(book) = self.lines.get(self.selected).map(|l| l.book.clone()) else {
+            return;
+        };
+        let i = self.books.iter().position(|(b, _)| *b == book).unwrap_or(0);
+        let target = if forward {
+            (i + 1).min(self.books.len() - 1)
+        } else {
+            i.saturating_sub(1)
+        };
+        self.selected = self.books[..target].iter().map(|(_, n)| n).sum();
+    }
+}
+
+/// `word` as a file name: letters, digits and dashes only.
+fn file_name(word: &str) -> String {
+    word.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .trim_matches('-')
+        .to_owned()
+}
+
+impl Component for Concordance {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_bible_handler(&mut self, bible: Arc<Bible>, _: &Translation) -> Result<()> {
+        self.bible = bible;
+        if !self.word.is_empty() {
+            self.build();
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Concordance(word) if word.is_empty() => self.editing = true,
+            Action::Concordance(word) => {
+                self.input = word;
+                self.build();
+                self.editing = self.lines.is_empty();
+            }
+            Action::Error(e) => self.status = Some(Err(e)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_key_event(&mut self, key: crossterm::event::KeyEvent) -> Result<Option<Action>> {
+        use crossterm::event::KeyCode::*;
+        if self.editing {
+            match key.code {
+                Esc => return Ok(Some(Action::ClosePopup)),
+                Enter => {
+                    self.build();
+                    self.editing = self.lines.is_empty();
+                }
+                Down | Tab if !self.lines.is_empty() => self.editing = false,
+                Backspace => {
+                    self.input.pop();
+                }
+                Char(c) => self.input.push(c),
+                _ => {}
+            }
+            return Ok(None);
+        }
+        match key.code {
+            Up | Char('k') => self.selected = self.selected.saturating_sub(1),
+            Down | Char('j') if self.selected + 1 < self.lines.len() => self.selected += 1,
+            Tab => self.skip_book(true),
+            BackTab => self.skip_book(false),
+            Char('/') | Char('i') => self.editing = true,
+            Char('e') => match self.export() {
+                Ok(path) => self.status = Some(Ok(format!("exported to {}", path.display()))),
+                Err(e) => return Ok(Some(Action::Error(format!("{e:#}")))),
+            },
+            Enter => {
+                if let (Some(l), Some(tx)) = (self.lines.get(self.selected), &self.action_tx) {
+                    tx.send(Action::MarkMatches {
+                        query: format!("={}", self.word),
+                        mode: if search::fold(&self.word) == self.word {
+                            Mode::Folded
+                        } else {
+                            Mode::Words
+                        },
+                    })?;
+                    tx.send(Action::OpenPassage {
+                        book: l.book.clone(),
+                        chapter: l.chapter,
+                    })?;
+                    tx.send(Action::SelectVerse {
+                        verse: l.verse,
+                        end: None,
+                    })?;
+                    return Ok(Some(Action::ClosePopup));
+                }
+            }
+            Esc | Char('q') => return Ok(Some(Action::ClosePopup)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame, area: Rect, focus: Focus) -> Result<()> {
+        if focus != Focus::Concordance {
+            return Ok(());
+        }
+        let hits = self
+            .lines
+            .iter()
+            .map(|l| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{:<20}", format!("{} {}:{}", l.book, l.chapter, l.verse)),
+                        Style::default().dim(),
+                    ),
+                    Span::raw(format!("{:>CONTEXT$} ", l.before.trim())),
+                    Span::styled(l.word.as_str(), Style::default().bold().yellow()),
+                    Span::raw(format!(" {}", l.after.trim_start())),
+                ]))
+            })
+            .collect();
+        self.list_state.select(Some(self.selected));
+        HitList {
+            title: "Concordance".into(),
+            input: &self.input,
+            editing: self.editing,
+            message: match &self.status {
+                Some(Ok(message)) => Some(Span::raw(message).dim()),
+                Some(Err(e)) => Some(Span::raw(e).red()),
+                None => None,
+            },
+            books: &self.books,
+            current: self.lines.get(self.selected).map(|l| l.book.as_str()),
+            hits_title: format!(
+                "{} occurrences of “{}” · e to export",
+                self.lines.len(),
+                self.word
+            ),
+            hits,
+        }
+        .render(f, area, &mut self.list_state);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_export() {
+        assert_eq!(file_name("λόγος"), "λόγος");
+        assert_eq!(file_name("../etc/passwd"), "etc-passwd");
+        assert_eq!(file_name("/"), "");
+
+        let mut concordance = Concordance::new();
+        concordance.word = "grace".into();
+        assert!(concordance.export().is_err());
+        concordance.editing = false;
+        let key = crossterm::event::KeyCode::Char('e').into();
+        assert_eq!(
+            concordance.handle_key_event(key).unwrap(),
+            Some(Action::Error("nothing to export".into()))
+        );
+    }
+
+    #[test]
+    fn test_build() {
+        let verse = |book: &str, chapter, verse, text: &str| crate::bible::Verse {
+            book: book.into(),
+            chapter,
+            verse,
+            text: text.into(),
+            ..Default::default()
+        };
+        let long = "Now these are the generations of the sons of Noah, the Word";
+        let mut bible = Bible::default();
+        bible.verses = vec![
+            verse("Genesis", 10, 1, long),
+            verse(
+                "John",
+                1,
+                1,
+                "In the beginning was the Word, and the Word was God.",
+            ),
+            verse("John", 1, 14, "And the Wórd was made flesh."),
+            verse("Romans", 10, 17, "Hearing by the word of God."),
+        ];
+        let mut concordance = Concordance::new();
+        concordance
+            .register_bible_handler(Arc::new(bible), &Translation::default())
+            .unwrap();
+        concordance.input = "WORD ".into();
+        concordance.build();
+
+        let split: Vec<_> = concordance
+            .lines
+            .iter()
+            .map(|l| (l.before.as_str(), l.word.as_str(), l.after.as_str()))
+            .collect();
+        assert_eq!(
+            split,
+            [
+                // Only the last 40 characters before the word are kept.
+                ("he generations of the sons of Noah, the ", "Word", ""),
+                (
+                    "In the beginning was the ",
+                    "Word",
+                    ", and the Word was God."
+                ),
+                (
+                    "In the beginning was the Word, and the ",
+                    "Word",
+                    " was God."
+                ),
+                // The accented spelling is found by the unaccented word.
+                ("And the ", "Wórd", " was made flesh."),
+                ("Hearing by the ", "word", " of God."),
+            ]
+        );
+        assert_eq!(
+            concordance.lines[0].before.chars().count(),
+            CONTEXT,
+            "context cut"
+        );
+        let books: Vec<_> = concordance
+            .books
+            .iter()
+            .map(|(b, n)| (b.as_str(), *n))
+            .collect();
+        assert_eq!(books, [("Genesis", 1), ("John", 3), ("Romans", 1)]);
+
+        concordance.selected = 2;
+        concordance.skip_book(true);
+        assert_eq!(concordance.selected, 4);
+        concordance.skip_book(true);
+        assert_eq!(concordance.selected, 4);
+        concordance.skip_book(false);
+        assert_eq!(concordance.selected, 1);
+        concordance.skip_book(false);
+        assert_eq!(concordance.selected, 0);
+        concordance.skip_book(false);
+        assert_eq!(concordance.selected, 0);
+    }
+}
@@ -0,0 +1,154 @@
+use std::sync::Arc;
+
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    action::Action,
+    app::Focus,
+    bible::{Bible, diff},
+    components::{Component, centered},
+    library::Translation,
+};
+
+/// Popup listing every verse of the current book that differs between the
+/// main translation and the first one shown beside it.
+pub struct Differences {
+    bible: Arc<Bible>,
+    other: Option<(String, Arc<Bible>)>,
+    book: String,
+    items: Vec<(u16, u16)>,
+    selected: usize,
+    list_state: ListState,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl Differences {
+    pub fn new() -> Self {
+        Self {
+            bible: Arc::default(),
+            other: None,
+            book: String::new(),
+            items: Vec::new(),
+            selected: 0,
+            list_state: ListState::default(),
+            action_tx: None,
+        }
+    }
+}
+
+impl Component for Differences {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_bible_handler(&mut self, bible: Arc<Bible>, _: &Translation) -> Result<()> {
+        self.bible = bible;
+        Ok(())
+    }
+
+    fn register_parallel_handler(&mut self, columns: &[(Translation, Arc<Bible>)]) -> Result<()> {
+        self.other = columns
+            .first()
+            .map(|(t, bible)| (t.abbrev.clone(), bible.clone()));
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::OpenPassage { book, .. } => self.book = book,
+            Action::ListDifferences => {
+                self.items = match &self.other {
+                    Some((_, other)) => diff::differing_verses(&self.bible, other, &self.book),
+                    None => Vec::new(),
+                };
+                self.selected = 0;
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_key_event(&mut self, key: crossterm::event::KeyEvent) -> Result<Option<Action>> {
+        use crossterm::event::KeyCode::*;
+        match key.code {
+            Up | Char('k') => self.selected = self.selected.saturating_sub(1),
+            Down | Char('j') if self.selected + 1 < self.items.len() => self.selected += 1,
+            Enter => {
+                if let (Some(&(chapter, verse)), Some(tx)) =
+                    (self.items.get(self.selected), &self.action_tx)
+                {
+                    tx.send(Action::OpenPassage {
+                        book: self.book.clone(),
+                        chapter,
+                    })?;
+                    tx.send(Action::SelectVerse { verse, end: None })?;
+                    return Ok(Some(Action::ClosePopup));
+                }
+            }
+            Esc | Char('q') => return Ok(Some(Action::ClosePopup)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame, area: Rect, focus: Focus) -> Result<()> {
+        if focus != Focus::Differences {
+            return Ok(());
+        }
+        let popup = centered(area, area.width * 3 / 4, area.height * 3 / 4);
+        let title = match &self.other {
+            Some((abbrev, _)) => format!(
+                "{}: {} differing verses vs {abbrev}",
+                self.book,
+                self.items.len()
+            ),
+            None => "Differences".into(),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Green));
+        f.render_widget(Clear, popup);
+
+        if self.other.is_none() {
+            f.render_widget(
+                Paragraph::new(
+                    "Show a second translation side by side (t, then Space) to compare.",
+                )
+                .wrap(Wrap { trim: true })
+                .block(block),
+                popup,
+            );
+            return Ok(());
+        }
+
+        let items: Vec<ListItem> = self
+            .items
+            .iter()
+            .map(|&(chapter, verse)| {
+                let text = self
+                    .bible
+                    .passage(&self.book, chapter)
+                    .into_iter()
+                    .find(|v| v.verse == verse)
+                    .map_or("", |v| v.text.as_str());
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{chapter:>3}:{verse:<3} ")),
+                    Span::styled(text, Style::default().dim()),
+                ]))
+            })
+            .collect();
+        self.list_state.select(Some(self.selected));
+        f.render_stateful_widget(
+            List::new(items)
+                .block(block)
+                .highlight_style(Style::default().bold().reversed()),
+            popup,
+            &mut self.list_state,
+        );
+        Ok(())
+    }
+}
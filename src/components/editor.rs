@@ -0,0 +1,220 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    action::Action,
+    app::Focus,
+    bible::reference::VerseRange,
+    components::{Component, centered},
+    notes::Note,
+};
+
+/// Multi-line editor for a note, opened over the reader.
+pub struct NoteEditor {
+    note: Note,
+    lines: Vec<String>,
+    /// Cursor line, and its position in that line in characters.
+    row: usize,
+    col: usize,
+    scroll: (u16, u16),
+}
+
+impl NoteEditor {
+    pub fn new() -> Self {
+        Self {
+            note: Note::default(),
+            lines: vec![String::new()],
+            row: 0,
+            col: 0,
+            scroll: (0, 0),
+        }
+    }
+
+    fn open(&mut self, note: Note) {
+        self.lines = note.body.lines().map(str::to_owned).collect();
+        if self.lines.is_empty() {
+            self.lines.push(String::new());
+        }
+        self.row = self.lines.len() - 1;
+        self.col = self.lines[self.row].chars().count();
+        self.scroll = (0, 0);
+        self.note = note;
+    }
+
+    /// The note with the text as typed.
+    fn edited(&self) -> Note {
+        Note {
+            body: self.lines.join("\n"),
+            ..self.note.clone()
+        }
+    }
+
+    /// Byte offset of the cursor in its line.
+    fn byte(&self) -> usize {
+        let line = &self.lines[self.row];
+        line.char_indices()
+            .nth(self.col)
+            .map_or(line.len(), |(i, _)| i)
+    }
+
+    fn line_len(&self) -> usize {
+        self.lines[self.row].chars().count()
+    }
+
+    fn edit(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char(c) => {
+                let at = self.byte();
+                self.lines[self.row].insert(at, c);
+                self.col += 1;
+            }
+            KeyCode::Tab => {
+                let at = self.byte();
+                self.lines[self.row].insert_str(at, "    ");
+                self.col += 4;
+            }
+            KeyCode::Enter => {
+                let at = self.byte();
+                let rest = self.lines[self.row].split_off(at);
+                self.row += 1;
+                self.lines.insert(self.row, rest);
+                self.col = 0;
+            }
+            KeyCode::Backspace if self.col > 0 => {
+                self.col -= 1;
+                let at = self.byte();
+                self.lines[self.row].remove(at);
+            }
+            KeyCode::Backspace if self.row > 0 => {
+                let line = self.lines.remove(self.row);
+                self.row -= 1;
+                self.col = self.line_len();
+                self.lines[self.row].push_str(&line);
+            }
+            KeyCode::Delete if self.col < self.line_len() => {
+                let at = self.byte();
+                self.lines[self.row].remove(at);
+            }
+            KeyCode::Delete if self.row + 1 < self.lines.len() => {
+                let line = self.lines.remove(self.row + 1);
+                self.lines[self.row].push_str(&line);
+            }
+            KeyCode::Left if self.col > 0 => self.col -= 1,
+            KeyCode::Left if self.row > 0 => {
+                self.row -= 1;
+                self.col = self.line_len();
+            }
+            KeyCode::Right if self.col < self.line_len() => self.col += 1,
+            KeyCode::Right if self.row + 1 < self.lines.len() => {
+                self.row += 1;
+                self.col = 0;
+            }
+            KeyCode::Up if self.row > 0 => {
+                self.row -= 1;
+                self.col = self.col.min(self.line_len());
+            }
+            KeyCode::Down if self.row + 1 < self.lines.len() => {
+                self.row += 1;
+                self.col = self.col.min(self.line_len());
+            }
+            KeyCode::Home => self.col = 0,
+            KeyCode::End => self.col = self.line_len(),
+            _ => {}
+        }
+    }
+}
+
+impl Component for NoteEditor {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if let Action::OpenNote(note) = action {
+            self.open(note);
+        }
+        Ok(None)
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Ok(match key.code {
+                KeyCode::Char('s') => Some(Action::SaveNote(self.edited())),
+                KeyCode::Char('e') => Some(Action::EditNoteExternally(self.edited())),
+                _ => None,
+            });
+        }
+        match key.code {
+            KeyCode::Esc => Ok(Some(Action::ClosePopup)),
+            code => {
+                self.edit(code);
+                Ok(None)
+            }
+        }
+    }
+
+    fn draw(&mut self, f: &mut Frame, area: Rect, focus: Focus) -> Result<()> {
+        if focus != Focus::NoteEditor {
+            return Ok(());
+        }
+        let popup = centered(area, area.width * 7 / 10, area.height * 7 / 10);
+        f.render_widget(Clear, popup);
+        let block = Block::default()
+            .title(format!("Note · {}", self.note.reference()))
+            .title_bottom(Line::from("Ctrl-s save · Ctrl-e $EDITOR · Esc discard").dim())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Green));
+        let inner = block.inner(popup);
+
+        // Scroll just enough to keep the cursor in view.
+        let x = self.lines[self.row][..self.byte()].width() as u16;
+        let (mut top, mut left) = self.scroll;
+        let (height, width) = (inner.height.max(1), inner.width.max(1));
+        top = top.clamp(
+            (self.row as u16 + 1).saturating_sub(height),
+            self.row as u16,
+        );
+        left = left.clamp((x + 1).saturating_sub(width), x);
+        self.scroll = (top, left);
+
+        let lines: Vec<Line> = self.lines.iter().map(|l| Line::raw(l.as_str())).collect();
+        f.render_widget(
+            Paragraph::new(lines).block(block).scroll(self.scroll),
+            popup,
+        );
+        f.set_cursor_position((inner.x + x - left, inner.y + self.row as u16 - top));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_edit() {
+        let mut editor = NoteEditor::new();
+        editor.open(Note {
+            body: "Grace".into(),
+            ..Default::default()
+        });
+        for code in [
+            KeyCode::Enter,
+            KeyCode::Char('é'),
+            KeyCode::Char('x'),
+            KeyCode::Left,
+            KeyCode::Backspace,
+            KeyCode::Up,
+            KeyCode::End,
+            KeyCode::Delete,
+        ] {
+            editor.edit(code);
+        }
+        assert_eq!(editor.edited().body, "Gracex");
+        editor.edit(KeyCode::Home);
+        editor.edit(KeyCode::Backspace);
+        editor.edit(KeyCode::Right);
+        editor.edit(KeyCode::Enter);
+        assert_eq!(editor.edited().body, "G\nracex");
+    }
+}
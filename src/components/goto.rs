@@ -0,0 +1,206 @@
+use std::sync::Arc;
+
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    action::Action,
+    app::Focus,
+    bible::{Bible, reference},
+    components::{Component, centered},
+    library::Translation,
+};
+
+/// Prompt for a reference such as `Ps 119:105`, opened with `g` or `:`.
+pub struct Goto {
+    bible: Arc<Bible>,
+    input: String,
+    error: Option<String>,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl Goto {
+    pub fn new() -> Self {
+        Self {
+            bible: Arc::default(),
+            input: String::new(),
+            error: None,
+            action_tx: None,
+        }
+    }
+
+    /// Open the first passage of the input, with its verses highlighted when
+    /// it is a range.
+    fn go(&mut self) -> Result<Option<Action>> {
+        let passage = match reference::parse(&self.input) {
+            Ok(passages) => passages[0].clone(),
+            Err(e) => {
+                self.error = Some(e.to_string());
+                return Ok(None);
+            }
+        };
+        let verses = match passage.resolve(&self.bible) {
+            Ok(verses) => verses,
+            Err(e) => {
+                self.error = Some(e.to_string());
+                return Ok(None);
+            }
+        };
+        let Some(tx) = &self.action_tx else {
+            return Ok(None);
+        };
+        let (start, end) = (passage.start, passage.end);
+        tx.send(Action::OpenPassage {
+            book: passage.book.clone(),
+            chapter: start.chapter,
+        })?;
+        let end = match start.verse {
+            Some(_) if end.chapter > start.chapter => Some(u16::MAX),
+            Some(v) if end.verse != Some(v) => end.verse,
+            _ => None,
+        };
+        tx.send(Action::SelectVerse {
+            verse: verses.first().map_or(1, |v| v.verse),
+            end,
+        })?;
+        Ok(Some(Action::ClosePopup))
+    }
+}
+
+impl Component for Goto {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_bible_handler(&mut self, bible: Arc<Bible>, _: &Translation) -> Result<()> {
+        self.bible = bible;
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if action == Action::Goto {
+            self.input.clear();
+            self.error = None;
+        }
+        Ok(None)
+    }
+
+    fn handle_key_event(&mut self, key: crossterm::event::KeyEvent) -> Result<Option<Action>> {
+        use crossterm::event::KeyCode::*;
+        match key.code {
+            Esc => return Ok(Some(Action::ClosePopup)),
+            Enter => return self.go(),
+            Backspace => {
+                self.input.pop();
+            }
+            Char(c) => self.input.push(c),
+            _ => {}
+        }
+        self.error = None;
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame, area: Rect, focus: Focus) -> Result<()> {
+        if focus != Focus::Goto {
+            return Ok(());
+        }
+        let height = if self.error.is_some() { 4 } else { 3 };
+        let popup = centered(area, area.width.clamp(20, 60), height);
+        let mut lines = vec![Line::from(vec![
+            Span::raw(&self.input),
+            Span::raw(" ").reversed(),
+        ])];
+        if let Some(e) = &self.error {
+            lines.push(Line::from(e.as_str()).style(Style::default().fg(Color::Red)));
+        }
+        f.render_widget(Clear, popup);
+        f.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .title("Go to")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Green)),
+            ),
+            popup,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::bible::Verse;
+
+    fn goto(input: &str) -> (Option<Action>, Vec<Action>) {
+        let mut bible = Bible::default();
+        bible.verses = (1..=4)
+            .flat_map(|chapter| {
+                (1..=20).map(move |verse| Verse {
+                    book: "John".into(),
+                    chapter,
+                    verse,
+                    text: "x".into(),
+                    ..Default::default()
+                })
+            })
+            .collect();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut goto = Goto::new();
+        goto.register_action_handler(tx).unwrap();
+        goto.register_bible_handler(Arc::new(bible), &Translation::default())
+            .unwrap();
+        goto.input = input.into();
+        let closed = goto.go().unwrap();
+        let mut sent = Vec::new();
+        while let Ok(action) = rx.try_recv() {
+            sent.push(action);
+        }
+        (closed, sent)
+    }
+
+    #[test]
+    fn test_goto_range() {
+        let open = Action::OpenPassage {
+            book: "John".into(),
+            chapter: 3,
+        };
+        let (closed, sent) = goto("John 3:16-18");
+        assert_eq!(closed, Some(Action::ClosePopup));
+        assert_eq!(
+            sent,
+            [
+                open.clone(),
+                Action::SelectVerse {
+                    verse: 16,
+                    end: Some(18)
+                }
+            ]
+        );
+        assert_eq!(
+            goto("John 3:16").1,
+            [
+                open.clone(),
+                Action::SelectVerse {
+                    verse: 16,
+                    end: None
+                }
+            ]
+        );
+        assert_eq!(
+            goto("John 3:16-4:2").1,
+            [
+                open,
+                Action::SelectVerse {
+                    verse: 16,
+                    end: Some(u16::MAX)
+                }
+            ]
+        );
+        assert_eq!(goto("John 9:1"), (None, Vec::new()));
+    }
+}
@@ -0,0 +1,222 @@
+use std::{collections::HashMap, sync::Arc};
+
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    action::Action,
+    app::{Focus, Mode},
+    bible::Bible,
+    components::{Component, list_popup},
+    config::Config,
+    highlights::{Highlight, Highlights, STYLE_PREFIX},
+    library::Translation,
+};
+
+/// The highlights of the open translation in bible order, with the text they
+/// cover, narrowed to one colour with Tab.
+pub struct HighlightList {
+    bible: Arc<Bible>,
+    translation: String,
+    highlights: Highlights,
+    styles: HashMap<String, Style>,
+    /// Colours named by the `highlight_` styles, sorted.
+    colours: Vec<String>,
+    /// Index into `colours` of the one shown, or all of them.
+    filter: Option<usize>,
+    shown: Vec<(Highlight, String)>,
+    selected: usize,
+    list_state: ListState,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl HighlightList {
+    pub fn new() -> Self {
+        Self {
+            bible: Arc::default(),
+            translation: String::new(),
+            highlights: Highlights::default(),
+            styles: HashMap::new(),
+            colours: Vec::new(),
+            filter: None,
+            shown: Vec::new(),
+            selected: 0,
+            list_state: ListState::default(),
+            action_tx: None,
+        }
+    }
+
+    fn refresh(&mut self) {
+        let colour = self.filter.and_then(|i| self.colours.get(i));
+        let mut shown: Vec<&Highlight> = self
+            .highlights
+            .list
+            .iter()
+            .filter(|h| h.translation == self.translation)
+            .filter(|h| colour.is_none_or(|c| *c == h.colour))
+            .collect();
+        shown.sort_by(|a, b| a.position().cmp(&b.position()));
+        self.shown = shown
+            .into_iter()
+            .map(|h| (h.clone(), self.text(h)))
+            .collect();
+        self.selected = self.selected.min(self.shown.len().saturating_sub(1));
+    }
+
+    /// The text a highlight covers, its verses joined by spaces.
+    fn text(&self, h: &Highlight) -> String {
+        self.bible
+            .passage(&h.book, h.chapter)
+            .into_iter()
+            .filter_map(|v| {
+                let cols = h.columns(v.verse, v.text.graphemes(true).count())?;
+                Some(
+                    v.text
+                        .graphemes(true)
+                        .skip(cols.start)
+                        .take(cols.len())
+                        .collect::<String>(),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn step_filter(&mut self, forward: bool) {
+        let n = self.colours.len();
+        self.filter = match (self.filter, forward) {
+            (None, true) if n > 0 => Some(0),
+            (None, false) if n > 0 => Some(n - 1),
+            (Some(i), true) if i + 1 < n => Some(i + 1),
+            (Some(i), false) if i > 0 => Some(i - 1),
+            _ => None,
+        };
+        self.selected = 0;
+        self.refresh();
+    }
+}
+
+impl Component for HighlightList {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.styles = config.styles.get(&Mode::Home).cloned().unwrap_or_default();
+        self.colours = self
+            .styles
+            .keys()
+            .filter_map(|k| k.strip_prefix(STYLE_PREFIX))
+            .map(str::to_owned)
+            .collect();
+        self.colours.sort();
+        Ok(())
+    }
+
+    fn register_bible_handler(
+        &mut self,
+        bible: Arc<Bible>,
+        translation: &Translation,
+    ) -> Result<()> {
+        self.bible = bible;
+        self.translation = translation.abbrev.clone();
+        self.refresh();
+        Ok(())
+    }
+
+    fn register_highlights_handler(&mut self, highlights: &Highlights) -> Result<()> {
+        self.highlights = highlights.clone();
+        self.refresh();
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: crossterm::event::KeyEvent) -> Result<Option<Action>> {
+        use crossterm::event::KeyCode::*;
+        let selected = self.shown.get(self.selected).map(|(h, _)| h);
+        match key.code {
+            Up | Char('k') => self.selected = self.selected.saturating_sub(1),
+            Down | Char('j') if self.selected + 1 < self.shown.len() => self.selected += 1,
+            Tab => self.step_filter(true),
+            BackTab => self.step_filter(false),
+            Char('d') | Delete => {
+                if let Some(h) = selected {
+                    return Ok(Some(Action::DeleteHighlight(h.clone())));
+                }
+            }
+            Enter => {
+                if let (Some(h), Some(tx)) = (selected, &self.action_tx) {
+                    tx.send(Action::OpenPassage {
+                        book: h.book.clone(),
+                        chapter: h.chapter,
+                    })?;
+                    tx.send(Action::SelectVerse {
+                        verse: h.start.0,
+                        end: None,
+                    })?;
+                    return Ok(Some(Action::ClosePopup));
+                }
+            }
+            Esc | Char('q') => return Ok(Some(Action::ClosePopup)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame, area: Rect, focus: Focus) -> Result<()> {
+        if focus != Focus::Highlights {
+            return Ok(());
+        }
+        let style = |colour: &str| {
+            self.styles
+                .get(&format!("{STYLE_PREFIX}{colour}"))
+                .copied()
+                .unwrap_or_default()
+        };
+
+        let items: Vec<ListItem> = self
+            .shown
+            .iter()
+            .map(|(h, text)| {
+                let reference = if h.start.0 == h.end.0 {
+                    format!("{} {}:{}", h.book, h.chapter, h.start.0)
+                } else {
+                    format!("{} {}:{}–{}", h.book, h.chapter, h.start.0, h.end.0)
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!(" {:<8}", h.colour), style(&h.colour)),
+                    Span::raw(format!(" {reference:<24}")),
+                    Span::styled(text.as_str(), Style::default().dim()),
+                ]))
+            })
+            .collect();
+
+        // Every colour, the one filtered on marked.
+        let mut filter = vec![Span::raw("Tab ")];
+        for (i, colour) in self.colours.iter().enumerate() {
+            let span = Span::styled(format!(" {colour} "), style(colour));
+            filter.push(if self.filter == Some(i) {
+                span.bold().underlined()
+            } else {
+                span
+            });
+            filter.push(Span::raw(" "));
+        }
+        let title = match self.filter.and_then(|i| self.colours.get(i)) {
+            Some(colour) => format!("Highlights · {} · {colour}", self.translation),
+            None => format!("Highlights · {}", self.translation),
+        };
+        list_popup(
+            f,
+            area,
+            title,
+            Line::from(filter),
+            items,
+            self.selected,
+            &mut self.list_state,
+        );
+        Ok(())
+    }
+}
@@ -1,8 +1,12 @@
+use std::sync::Arc;
+
 use color_eyre::Result;
 use ratatui::{prelude::*, widgets::*};
 use tokio::sync::mpsc::UnboundedSender;
 
-use crate::{action::Action, app::Focus, bible::Bible, components::Component};
+use crate::{
+    action::Action, app::Focus, bible::Bible, components::Component, library::Translation,
+};
 
 pub struct Index {
     all_items: Vec<(String, u16)>,
@@ -20,14 +24,12 @@ enum Mode {
 }
 
 impl Index {
-    pub fn new(bible: Bible) -> Self {
-        let all_items = bible.chapters();
-        let items = all_items.clone();
+    pub fn new() -> Self {
         let mut list_state = ListState::default();
         list_state.select(Some(0));
         Self {
-            all_items,
-            items,
+            all_items: Vec::new(),
+            items: Vec::new(),
             selected: 0,
             list_state,
             mode: Mode::Normal,
@@ -73,6 +75,24 @@ impl Component for Index {
         self.action_tx = Some(tx);
         Ok(())
     }
+    fn register_bible_handler(&mut self, bible: Arc<Bible>, _: &Translation) -> Result<()> {
+        self.all_items = bible.chapters();
+        self.apply_filter(&self.current_filter.clone());
+        Ok(())
+    }
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        // Follow passages opened elsewhere, e.g. after a translation switch.
+        if let Action::OpenPassage { book, chapter } = action
+            && let Some(i) = self
+                .items
+                .iter()
+                .position(|(b, c)| *b == book && *c == chapter)
+        {
+            self.selected = i;
+        }
+        Ok(None)
+    }
+    #[allow(clippy::collapsible_match)]
     fn handle_key_event(&mut self, key: crossterm::event::KeyEvent) -> Result<Option<Action>> {
         use crossterm::event::KeyCode::*;
         match &mut self.mode {
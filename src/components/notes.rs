@@ -0,0 +1,185 @@
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    action::Action,
+    app::Focus,
+    bible::reference::VerseRange,
+    components::{Component, centered, pane, query_line},
+    notes::{Note, Notes},
+};
+
+/// Every note, searched as the query is typed, with the selected one shown
+/// in full.
+pub struct NoteList {
+    notes: Notes,
+    input: String,
+    /// Whether keys go to the query line rather than the list.
+    editing: bool,
+    shown: Vec<Note>,
+    selected: usize,
+    list_state: ListState,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl NoteList {
+    pub fn new() -> Self {
+        Self {
+            notes: Notes::default(),
+            input: String::new(),
+            editing: true,
+            shown: Vec::new(),
+            selected: 0,
+            list_state: ListState::default(),
+            action_tx: None,
+        }
+    }
+
+    fn search(&mut self) {
+        self.shown = self
+            .notes
+            .search(&self.input)
+            .into_iter()
+            .cloned()
+            .collect();
+        self.selected = self.selected.min(self.shown.len().saturating_sub(1));
+    }
+}
+
+impl Component for NoteList {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_notes_handler(&mut self, notes: &Notes) -> Result<()> {
+        self.notes = notes.clone();
+        self.search();
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if action == Action::Notes {
+            self.editing = true;
+        }
+        Ok(None)
+    }
+
+    fn handle_key_event(&mut self, key: crossterm::event::KeyEvent) -> Result<Option<Action>> {
+        use crossterm::event::KeyCode::*;
+        if self.editing {
+            match key.code {
+                Esc => return Ok(Some(Action::ClosePopup)),
+                Enter | Down | Tab if !self.shown.is_empty() => self.editing = false,
+                Backspace => {
+                    self.input.pop();
+                    self.selected = 0;
+                    self.search();
+                }
+                Char(c) => {
+                    self.input.push(c);
+                    self.selected = 0;
+                    self.search();
+                }
+                _ => {}
+            }
+            return Ok(None);
+        }
+        let selected = self.shown.get(self.selected);
+        match key.code {
+            Up | Char('k') => self.selected = self.selected.saturating_sub(1),
+            Down | Char('j') if self.selected + 1 < self.shown.len() => self.selected += 1,
+            Char('/') | Char('i') | Tab => self.editing = true,
+            Char('e') => {
+                if let Some(note) = selected {
+                    return Ok(Some(Action::OpenNote(note.clone())));
+                }
+            }
+            Char('d') | Delete => {
+                if let Some(note) = selected {
+                    return Ok(Some(Action::DeleteNote(note.clone())));
+                }
+            }
+            Enter => {
+                if let (Some(note), Some(tx)) = (selected, &self.action_tx) {
+                    tx.send(Action::OpenPassage {
+                        book: note.book.clone(),
+                        chapter: note.chapter,
+                    })?;
+                    tx.send(Action::SelectVerse {
+                        verse: note.verse,
+                        end: None,
+                    })?;
+                    return Ok(Some(Action::ClosePopup));
+                }
+            }
+            Esc | Char('q') => return Ok(Some(Action::ClosePopup)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame, area: Rect, focus: Focus) -> Result<()> {
+        if focus != Focus::Notes {
+            return Ok(());
+        }
+        let popup = centered(area, area.width * 9 / 10, area.height * 9 / 10);
+        f.render_widget(Clear, popup);
+        let [prompt, body] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(popup);
+        let [list, preview] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(body);
+        query_line(f, prompt, "Search notes", &self.input, self.editing, None);
+
+        let items: Vec<ListItem> = self
+            .shown
+            .iter()
+            .map(|n| {
+                let first = n.body.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{:<20}", n.reference())),
+                    Span::styled(first, Style::default().dim()),
+                ]))
+            })
+            .collect();
+        self.list_state.select(Some(self.selected));
+        f.render_stateful_widget(
+            List::new(items)
+                .block(
+                    pane(!self.editing)
+                        .title(format!("{} notes", self.shown.len()))
+                        .title_bottom(Line::from("Enter jump · e edit · d delete").dim()),
+                )
+                .highlight_style(Style::default().reversed()),
+            list,
+            &mut self.list_state,
+        );
+
+        let (title, text) = match self.shown.get(self.selected) {
+            Some(n) => (n.reference(), markdown(&n.body)),
+            None => (String::new(), Text::default()),
+        };
+        f.render_widget(
+            Paragraph::new(text)
+                .block(pane(false).title(title))
+                .wrap(Wrap { trim: false }),
+            preview,
+        );
+        Ok(())
+    }
+}
+
+/// A note's Markdown for display: headings in bold, the rest as written.
+pub fn markdown(body: &str) -> Text<'_> {
+    body.lines()
+        .map(|line| {
+            if line.starts_with('#') {
+                Line::from(line.trim_start_matches('#').trim_start()).bold()
+            } else {
+                Line::from(line)
+            }
+        })
+        .collect()
+}
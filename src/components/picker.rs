@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+
+use crate::{
+    action::Action,
+    app::Focus,
+    bible::Bible,
+    components::{Component, centered},
+    library::{Library, Translation},
+};
+
+/// Popup listing the installed translations; shown while it has focus.
+pub struct TranslationPicker {
+    translations: Vec<Translation>,
+    current: String,
+    /// Translations shown in the parallel view.
+    parallel: Vec<String>,
+    selected: usize,
+    list_state: ListState,
+    error: Option<String>,
+}
+
+impl TranslationPicker {
+    pub fn new(library: &Library) -> Self {
+        Self {
+            translations: library.translations.clone(),
+            current: String::new(),
+            parallel: Vec::new(),
+            selected: 0,
+            list_state: ListState::default(),
+            error: None,
+        }
+    }
+}
+
+impl Component for TranslationPicker {
+    fn register_bible_handler(
+        &mut self,
+        _bible: Arc<Bible>,
+        translation: &Translation,
+    ) -> Result<()> {
+        self.current = translation.abbrev.clone();
+        Ok(())
+    }
+
+    fn register_parallel_handler(&mut self, columns: &[(Translation, Arc<Bible>)]) -> Result<()> {
+        self.error = None;
+        self.parallel = columns.iter().map(|(t, _)| t.abbrev.clone()).collect();
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::PickTranslation => {
+                self.error = None;
+                self.selected = self
+                    .translations
+                    .iter()
+                    .position(|t| t.abbrev == self.current)
+                    .unwrap_or(0);
+            }
+            Action::Error(e) => self.error = Some(e),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_key_event(&mut self, key: crossterm::event::KeyEvent) -> Result<Option<Action>> {
+        use crossterm::event::KeyCode::*;
+        match key.code {
+            Up | Char('k') => self.selected = self.selected.saturating_sub(1),
+            Down | Char('j') if self.selected + 1 < self.translations.len() => self.selected += 1,
+            Enter => {
+                if let Some(t) = self.translations.get(self.selected) {
+                    return Ok(Some(Action::SwitchTranslation(t.abbrev.clone())));
+                }
+            }
+            Char(' ') => {
+                if let Some(t) = self.translations.get(self.selected) {
+                    return Ok(Some(Action::ToggleParallel(t.abbrev.clone())));
+                }
+            }
+            Esc | Char('q') => return Ok(Some(Action::ClosePopup)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame, area: Rect, focus: Focus) -> Result<()> {
+        if focus != Focus::Picker {
+            return Ok(());
+        }
+        let width = self
+            .translations
+            .iter()
+            .map(|t| t.abbrev.len() + t.name.len() + t.language.len() + 12)
+            .max()
+            .unwrap_or(0)
+            .max(40) as u16;
+        let height = self.translations.len() as u16 + 2 + u16::from(self.error.is_some()) * 2;
+        let popup = centered(area, width, height);
+
+        let items: Vec<ListItem> = self
+            .translations
+            .iter()
+            .map(|t| {
+                let mark = if t.abbrev == self.current {
+                    "*"
+                } else if self.parallel.contains(&t.abbrev) {
+                    "+"
+                } else {
+                    " "
+                };
+                let mut line = Line::from(format!("{mark} {:<8} {}", t.abbrev, t.name));
+                if !t.language.is_empty() {
+                    line.push_span(Span::styled(
+                        format!(" ({})", t.language),
+                        Style::default().dim(),
+                    ));
+                }
+                ListItem::new(line)
+            })
+            .collect();
+        let block = Block::default()
+            .title("Translations")
+            .title_bottom(Line::from(" Enter: read · Space: side by side ").dim())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Green));
+        let inner = block.inner(popup);
+        f.render_widget(Clear, popup);
+        f.render_widget(block, popup);
+
+        let [list_area, error_area] = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(u16::from(self.error.is_some()) * 2),
+        ])
+        .areas(inner);
+        self.list_state.select(Some(self.selected));
+        f.render_stateful_widget(
+            List::new(items).highlight_style(Style::default().bold().reversed()),
+            list_area,
+            &mut self.list_state,
+        );
+        if let Some(e) = &self.error {
+            f.render_widget(
+                Paragraph::new(e.as_str())
+                    .style(Style::default().fg(Color::Red))
+                    .wrap(Wrap { trim: true }),
+                error_area,
+            );
+        }
+        Ok(())
+    }
+}
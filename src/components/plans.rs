@@ -0,0 +1,290 @@
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    action::Action,
+    app::Focus,
+    bible::reference,
+    components::{Component, list_popup},
+    plans::{Date, Plan, Plans, Progress},
+};
+
+/// Columns taken by a day's mark, number and date, before its readings.
+const HEAD_WIDTH: usize = 24;
+
+/// The reading plans, and the days of the one opened with their readings,
+/// which of them are done and which were missed.
+pub struct PlanView {
+    plans: Plans,
+    today: Date,
+    selected: usize,
+    /// Name of the plan opened, with (day, reading) for each of its readings.
+    opened: Option<(String, Vec<(usize, String)>)>,
+    row: usize,
+    error: Option<String>,
+    list_state: ListState,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl PlanView {
+    pub fn new() -> Self {
+        Self {
+            plans: Plans::default(),
+            today: Date::today(),
+            selected: 0,
+            opened: None,
+            row: 0,
+            error: None,
+            list_state: ListState::default(),
+            action_tx: None,
+        }
+    }
+
+    fn open(&mut self, plan: &Plan) {
+        let rows: Vec<(usize, String)> = plan
+            .days
+            .iter()
+            .enumerate()
+            .flat_map(|(day, readings)| readings.iter().map(move |r| (day, r.clone())))
+            .collect();
+        self.opened = Some((plan.name.clone(), rows));
+        self.row = 0;
+        self.go_to_day(self.due().unwrap_or(0));
+    }
+
+    fn plan(&self) -> Option<&Plan> {
+        let (name, _) = self.opened.as_ref()?;
+        self.plans.get(name)
+    }
+
+    fn progress(&self) -> Option<&Progress> {
+        let (name, _) = self.opened.as_ref()?;
+        self.plans.progress(name)
+    }
+
+    /// Today's day of the opened plan, once it is started.
+    fn due(&self) -> Option<usize> {
+        self.progress()?.due(self.today, self.plan()?.days.len())
+    }
+
+    fn go_to_day(&mut self, day: usize) {
+        if let Some((_, rows)) = &self.opened
+            && let Some(row) = rows.iter().position(|(d, _)| *d == day)
+        {
+            self.row = row;
+        }
+    }
+
+    /// Open a reading in the reader, at its first verse.
+    fn read(&mut self, reading: &str) -> Result<Option<Action>> {
+        let passage = match reference::parse(reading) {
+            Ok(passages) => passages[0].clone(),
+            Err(e) => {
+                self.error = Some(e.to_string());
+                return Ok(None);
+            }
+        };
+        let Some(tx) = &self.action_tx else {
+            return Ok(None);
+        };
+        let (start, end) = (passage.start, passage.end);
+        tx.send(Action::OpenPassage {
+            book: passage.book,
+            chapter: start.chapter,
+        })?;
+        tx.send(Action::SelectVerse {
+            verse: start.verse.unwrap_or(1),
+            end: match start.verse {
+                Some(_) if end.chapter > start.chapter => Some(u16::MAX),
+                Some(v) if end.verse != Some(v) => end.verse,
+                _ => None,
+            },
+        })?;
+        Ok(Some(Action::ClosePopup))
+    }
+
+    /// How the opened plan stands today.
+    fn status(&self) -> String {
+        let (Some(plan), Some(progress)) = (self.plan(), self.progress()) else {
+            return "not started · s to start today".to_owned();
+        };
+        let days = plan.days.len();
+        let missed = progress.missed(self.today, days).len();
+        match progress.due(self.today, days) {
+            None => format!("starts {}", progress.start),
+            _ if progress.done.len() == days => "finished".to_owned(),
+            Some(due) if missed == 0 => format!("day {} of {days} · on track", due + 1),
+            Some(due) => format!("day {} of {days} · {missed} behind", due + 1),
+        }
+    }
+}
+
+impl Component for PlanView {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_plans_handler(&mut self, plans: &Plans) -> Result<()> {
+        self.plans = plans.clone();
+        self.selected = self.selected.min(plans.list.len().saturating_sub(1));
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Plans => {
+                // The date may have changed since it was last open.
+                self.today = Date::today();
+                self.error = None;
+                if let Some(plan) = self.plan().cloned() {
+                    self.open(&plan);
+                }
+            }
+            Action::StartPlan(_) => self.today = Date::today(),
+            Action::Error(e) => self.error = Some(e),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_key_event(&mut self, key: crossterm::event::KeyEvent) -> Result<Option<Action>> {
+        use crossterm::event::KeyCode::*;
+        let Some((name, rows)) = &self.opened else {
+            match key.code {
+                Up | Char('k') => self.selected = self.selected.saturating_sub(1),
+                Down | Char('j') if self.selected + 1 < self.plans.list.len() => self.selected += 1,
+                Enter | Right | Char('l') => {
+                    if let Some(plan) = self.plans.list.get(self.selected).cloned() {
+                        self.open(&plan);
+                    }
+                }
+                Esc | Char('q') => return Ok(Some(Action::ClosePopup)),
+                _ => {}
+            }
+            return Ok(None);
+        };
+        let name = name.clone();
+        let day = rows.get(self.row).map(|(d, _)| *d);
+        match key.code {
+            Up | Char('k') => self.row = self.row.saturating_sub(1),
+            Down | Char('j') if self.row + 1 < rows.len() => self.row += 1,
+            Enter => {
+                if let Some((_, reading)) = rows.get(self.row).cloned() {
+                    return self.read(&reading);
+                }
+            }
+            Char(' ') | Char('x') => {
+                let (Some(day), Some(progress)) = (day, self.progress()) else {
+                    return Ok(None);
+                };
+                let done = !progress.done.contains(&day);
+                return Ok(Some(Action::MarkDay {
+                    plan: name,
+                    day,
+                    done,
+                }));
+            }
+            Char('t') => self.go_to_day(self.due().unwrap_or(0)),
+            // The first day missed, to catch up from.
+            Char('c') => {
+                if let (Some(plan), Some(progress)) = (self.plan(), self.progress())
+                    && let Some(&day) = progress.missed(self.today, plan.days.len()).first()
+                {
+                    self.go_to_day(day);
+                }
+            }
+            Char('s') if self.progress().is_none() => {
+                self.go_to_day(0);
+                return Ok(Some(Action::StartPlan(name)));
+            }
+            Char('X') => return Ok(Some(Action::StopPlan(name))),
+            Esc | Left | Backspace | Char('h') => self.opened = None,
+            Char('q') => return Ok(Some(Action::ClosePopup)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame, area: Rect, focus: Focus) -> Result<()> {
+        if focus != Focus::Plans {
+            return Ok(());
+        }
+        let (title, hint, items, selected) = match &self.opened {
+            None => {
+                let items: Vec<ListItem> = self
+                    .plans
+                    .list
+                    .iter()
+                    .map(|plan| {
+                        let days = plan.days.len();
+                        let status = match self.plans.progress(&plan.name) {
+                            Some(p) => {
+                                let read = p.done.len();
+                                match p.missed(self.today, days).len() {
+                                    0 => format!("{read}/{days} read"),
+                                    missed => format!("{read}/{days} read · {missed} behind"),
+                                }
+                            }
+                            None => format!("{days} days"),
+                        };
+                        ListItem::new(Line::from(vec![
+                            Span::raw(format!(" {:<44}", plan.name)),
+                            Span::styled(status, Style::default().dim()),
+                        ]))
+                    })
+                    .collect();
+                let title = format!("Reading plans · {}", self.today);
+                (title, "Enter open", items, self.selected)
+            }
+            Some((name, rows)) => {
+                let progress = self.progress();
+                let due = self.due();
+                let missed = progress.zip(self.plan()).map_or(Vec::new(), |(p, plan)| {
+                    p.missed(self.today, plan.days.len())
+                });
+                let items: Vec<ListItem> = rows
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (day, reading))| {
+                        let done = progress.is_some_and(|p| p.done.contains(day));
+                        // The day, its date and mark only on its first reading.
+                        let head = if i == 0 || rows[i - 1].0 != *day {
+                            let date = progress
+                                .map_or(String::new(), |p| p.start.plus(*day as i64).to_string());
+                            let mark = if done { "✓" } else { " " };
+                            let head = format!(" {mark} Day {:<4} {date}", day + 1);
+                            format!("{head:<HEAD_WIDTH$}")
+                        } else {
+                            " ".repeat(HEAD_WIDTH)
+                        };
+                        let style = if done {
+                            Style::default().dim()
+                        } else if missed.contains(day) {
+                            Style::default().red()
+                        } else if due == Some(*day) {
+                            Style::default().bold()
+                        } else {
+                            Style::default()
+                        };
+                        ListItem::new(Line::from(vec![
+                            Span::raw(head),
+                            Span::raw(reading.as_str()),
+                        ]))
+                        .style(style)
+                    })
+                    .collect();
+                let title = format!("{name} · {}", self.status());
+                let hint = "Enter read · x done · t today · c catch up · X stop · Esc back";
+                (title, hint, items, self.row)
+            }
+        };
+        let hint = match &self.error {
+            Some(error) => Line::from(error.as_str()).red(),
+            None => Line::from(hint).dim(),
+        };
+        list_popup(f, area, title, hint, items, selected, &mut self.list_state);
+        Ok(())
+    }
+}
@@ -1,58 +1,578 @@
+use std::{collections::HashMap, ops::Range, sync::Arc};
+
 use color_eyre::Result;
 use ratatui::{prelude::*, widgets::*};
 
-use crate::{action::Action, app::Focus, bible::Bible, components::Component};
+use crate::{
+    action::Action,
+    app::{Focus, Mode},
+    bible::{
+        Bible, BreakKind, Verse, diff,
+        reference::VerseRange,
+        search::{self, Pattern},
+    },
+    bookmarks::{Bookmark, Bookmarks},
+    components::{Component, notes::markdown},
+    config::Config,
+    highlights::{Highlight, Highlights, STYLE_PREFIX},
+    library::{Direction, Translation},
+    notes::{Note, Notes},
+    store,
+    tags::{self, Tag, Tags},
+};
 use arboard::Clipboard;
+use tokio::sync::mpsc::UnboundedSender;
+
+mod bidi;
+mod graphemes;
+
+/// The `/` or `?` line being typed.
+struct Prompt {
+    input: String,
+    backward: bool,
+    /// Cursor and scroll to go back to if the search is cancelled.
+    origin: (usize, usize, u16),
+    previous: Option<Pattern>,
+}
+
+/// The label and folder being typed for a new bookmark; Tab moves between
+/// them.
+struct Naming {
+    bookmark: Bookmark,
+    folder: bool,
+}
 
 pub struct Reader {
-    bible: Bible,
+    bible: Arc<Bible>,
+    /// Abbreviation of the translation being read, shown in the title.
+    translation: String,
+    /// Which way the main translation runs; `None` tells from each verse.
+    direction: Option<Direction>,
+    /// Translations shown beside the main one, as (abbreviation, text,
+    /// direction).
+    parallel: Vec<(String, Arc<Bible>, Option<Direction>)>,
+    /// Column with focus in parallel mode; 0 is the main translation.
+    column: usize,
+    /// Mark the words the first parallel column adds to or drops from the
+    /// main translation.
+    compare: bool,
+    /// Show the chapters before and after the open one around it, each under
+    /// a heading, and move on into them past its first and last verses.
+    continuous: bool,
+    /// Flow verses into the paragraphs of the source instead of one verse
+    /// per line.
+    prose: bool,
+    styles: HashMap<String, Style>,
+    /// Screen lines kept in view above and below the cursor.
+    scrolloff: usize,
+    /// Every chapter of the main translation in order, read once when it
+    /// is registered.
+    chapters: Vec<(String, u16)>,
     book: String,
     chapter: u16,
+    /// Verses of the open chapter to highlight, from a goto range.
+    passage: Option<(u16, u16)>,
+    /// The last search, whose matches are marked in every column.
+    search: Option<Pattern>,
+    /// Whether `n` repeats the last search upwards.
+    backward: bool,
+    prompt: Option<Prompt>,
+    bookmarks: Bookmarks,
+    naming: Option<Naming>,
+    highlights: Highlights,
+    /// Whether the next key picks the colour to highlight in.
+    choosing: bool,
+    notes: Notes,
+    tags: Tags,
+    /// Tag being named for the selection; its name is the input.
+    tagging: Option<Tag>,
+    /// Whether the note on the cursor's verse is shown beside the text.
+    note_pane: bool,
+    /// Message about the last search or bookmark, shown under the focused
+    /// column.
+    status: Option<String>,
     row: usize,
     col: usize,
+    /// Screen lines scrolled past, wrapped lines counting each of their
+    /// rows; kept around the cursor when drawing.
     scroll: u16,
+    /// The chapter last drawn in continuous mode and the screen row its
+    /// heading was on, kept there when reading on into a neighbouring one.
+    shown: Option<(String, u16, i64)>,
     visual: bool,
     anchor_row: usize,
     anchor_col: usize,
-    clipboard: Clipboard,
+    /// Opened on the first yank, as there may be no display to hold one.
+    clipboard: Option<Clipboard>,
+    action_tx: Option<UnboundedSender<Action>>,
 }
 
 impl Reader {
-    pub fn new(bible: Bible) -> Self {
+    pub fn new() -> Self {
         Self {
-            bible,
+            bible: Arc::default(),
+            translation: String::new(),
+            direction: None,
+            parallel: Vec::new(),
+            column: 0,
+            compare: false,
+            continuous: false,
+            prose: false,
+            styles: HashMap::new(),
+            scrolloff: 3,
+            chapters: Vec::new(),
             book: "Genesis".into(),
             chapter: 1,
+            passage: None,
+            search: None,
+            backward: false,
+            prompt: None,
+            bookmarks: Bookmarks::default(),
+            naming: None,
+            highlights: Highlights::default(),
+            choosing: false,
+            notes: Notes::default(),
+            tags: Tags::default(),
+            tagging: None,
+            note_pane: false,
+            status: None,
             row: 0,
             col: 0,
             scroll: 0,
+            shown: None,
             visual: false,
             anchor_row: 0,
             anchor_col: 0,
-            clipboard: Clipboard::new().unwrap(),
+            clipboard: None,
+            action_tx: None,
         }
     }
 
-    fn cur_line_len(&self) -> usize {
-        self.bible
+    /// Text of `row` in the focused column. Rows follow the verses of the
+    /// main translation; other columns are matched to them by verse number,
+    /// so a verse only another column has is never under the cursor.
+    fn row_text(&self, row: usize) -> Option<&str> {
+        let verse: &Verse = self.bible.passage(&self.book, self.chapter).get(row)?;
+        let Some((_, bible, _)) = self
+            .column
+            .checked_sub(1)
+            .and_then(|c| self.parallel.get(c))
+        else {
+            return Some(&verse.text);
+        };
+        bible
             .passage(&self.book, self.chapter)
-            .get(self.row)
-            .map(|v| v.text.len())
-            .unwrap_or(0)
+            .into_iter()
+            .find(|v| v.verse == verse.verse)
+            .map(|v| v.text.as_str())
+    }
+
+    /// Whether the cursor is at the end of the visual selection rather than
+    /// its start.
+    fn cursor_last(&self) -> bool {
+        self.visual && (self.anchor_row, self.anchor_col) <= (self.row, self.col)
+    }
+
+    /// Whether the cursor's verse reads right to left, so that `h` and `l`
+    /// move the other way through its text.
+    fn rtl_cursor(&self) -> bool {
+        let direction = match self.column.checked_sub(1) {
+            Some(c) => self.parallel[c].2,
+            None => self.direction,
+        };
+        direction
+            .or_else(|| self.row_text(self.row).and_then(bidi::detect))
+            .is_some_and(|d| d == Direction::Rtl)
+    }
+
+    /// Bible of the focused column.
+    fn column_bible(&self) -> &Bible {
+        match self.column.checked_sub(1) {
+            Some(c) => &self.parallel[c].1,
+            None => &self.bible,
+        }
+    }
+
+    /// Show another chapter from its first verse.
+    fn open(&mut self, book: String, chapter: u16) {
+        self.book = book;
+        self.chapter = chapter;
+        self.passage = None;
+        self.row = 0;
+        self.col = 0;
+        self.scroll = 0;
+        self.visual = false;
+    }
+
+    /// Open another chapter and tell the other components about it.
+    fn go_to(&mut self, book: String, chapter: u16) -> Result<()> {
+        self.open(book.clone(), chapter);
+        if let Some(tx) = &self.action_tx {
+            tx.send(Action::OpenPassage { book, chapter })?;
+        }
+        Ok(())
     }
 
-    fn ensure_visible(&mut self) {
-        let above = 3usize;
-        let below = 3usize;
-        if self.row < self.scroll as usize {
-            self.scroll = self.row as u16;
+    /// Open the chapter `delta` chapters away in bible order, if there is one.
+    fn step_chapter(&mut self, delta: isize) -> Result<bool> {
+        let next = self
+            .chapters
+            .iter()
+            .position(|(b, c)| *b == self.book && *c == self.chapter)
+            .and_then(|i| i.checked_add_signed(delta))
+            .and_then(|i| self.chapters.get(i));
+        let Some((book, chapter)) = next.cloned() else {
+            return Ok(false);
+        };
+        self.go_to(book, chapter)?;
+        Ok(true)
+    }
+
+    /// Open the first chapter of the book `delta` books away.
+    fn step_book(&mut self, delta: isize) -> Result<()> {
+        use itertools::Itertools;
+        let chapters = &self.chapters;
+        let books: Vec<&String> = chapters.iter().map(|(b, _)| b).dedup().collect();
+        let next = books
+            .iter()
+            .position(|b| **b == self.book)
+            .and_then(|i| i.checked_add_signed(delta))
+            .and_then(|i| books.get(i));
+        let Some(&book) = next else {
+            return Ok(());
+        };
+        let chapter = chapters
+            .iter()
+            .find(|(b, _)| b == book)
+            .map_or(1, |(_, c)| *c);
+        self.go_to(book.clone(), chapter)
+    }
+
+    /// Row and byte offset of every match of the search in the focused
+    /// column, in order.
+    fn matches(&self) -> Vec<(usize, usize)> {
+        let Some(pattern) = &self.search else {
+            return Vec::new();
+        };
+        let rows = self.bible.passage(&self.book, self.chapter).len();
+        (0..rows)
+            .flat_map(|r| {
+                let text = self.row_text(r).unwrap_or_default();
+                pattern
+                    .matches(text)
+                    .into_iter()
+                    .map(move |m| (r, graphemes::col(text, m.start)))
+            })
+            .collect()
+    }
+
+    /// Put the cursor on the nearest match after it, or before it going
+    /// `backward`; `at` itself counts when `inclusive`.
+    fn jump(&mut self, backward: bool, inclusive: bool) -> bool {
+        let at = (self.row, self.col);
+        let matches = self.matches();
+        let found = if backward {
+            matches
+                .iter()
+                .rev()
+                .find(|&&m| m < at || (inclusive && m == at))
         } else {
-            let low = self.scroll as usize;
-            let high = low + above + below;
-            if self.row > high {
-                self.scroll = self.row.saturating_sub(above + below) as u16;
+            matches.iter().find(|&&m| m > at || (inclusive && m == at))
+        };
+        let Some(&(row, col)) = found else {
+            return false;
+        };
+        (self.row, self.col) = (row, col);
+        true
+    }
+
+    /// Go to the next match in reading order, or the previous one, moving on
+    /// through the following chapters and around the bible if need be.
+    fn next_match(&mut self, backward: bool) -> Result<()> {
+        self.status = None;
+        if self.search.is_none() || self.jump(backward, false) {
+            return Ok(());
+        }
+        let Some(pattern) = &self.search else {
+            return Ok(());
+        };
+        let bible = self.column_bible();
+        let chapters = bible.chapters();
+        let len = chapters.len();
+        let here = chapters
+            .iter()
+            .position(|(b, c)| *b == self.book && *c == self.chapter)
+            .unwrap_or(0);
+        let found = (1..=len).find_map(|k| {
+            let i = if backward {
+                (here + len - k % len) % len
+            } else {
+                (here + k) % len
+            };
+            let (book, chapter) = &chapters[i];
+            bible
+                .passage(book, *chapter)
+                .iter()
+                .any(|v| !pattern.matches(&v.text).is_empty())
+                .then(|| (book.clone(), *chapter, i))
+        });
+        let Some((book, chapter, i)) = found else {
+            self.status = Some("pattern not found".into());
+            return Ok(());
+        };
+        if (backward && i >= here) || (!backward && i <= here) {
+            self.status = Some(if backward {
+                "search hit TOP, continuing at BOTTOM".into()
+            } else {
+                "search hit BOTTOM, continuing at TOP".into()
+            });
+        }
+        self.go_to(book.clone(), chapter)?;
+        if backward {
+            self.row = self.bible.passage(&book, chapter).len().saturating_sub(1);
+            self.col = usize::MAX;
+        }
+        self.jump(backward, true);
+        Ok(())
+    }
+
+    /// The word under the cursor, or the next one on its line.
+    fn cursor_word(&self) -> Option<String> {
+        let text = self.row_text(self.row)?;
+        let at = graphemes::byte(text, self.col);
+        search::words(text)
+            .into_iter()
+            .find(|(r, _)| r.contains(&at) || r.start > at)
+            .map(|(_, word)| word)
+    }
+
+    /// Search the chapter for the whole word under the cursor.
+    fn search_word(&mut self) -> Result<()> {
+        let Some(word) = self.cursor_word() else {
+            return Ok(());
+        };
+        self.search = Pattern::new(&format!("={word}"), search::Mode::Words).ok();
+        self.backward = false;
+        self.next_match(false)
+    }
+
+    /// Keys typed into the `/` or `?` line, which moves the cursor to the
+    /// first match as the search changes.
+    fn prompt_key(&mut self, key: crossterm::event::KeyEvent) -> Option<Action> {
+        use crossterm::event::KeyCode::*;
+        let prompt = self.prompt.as_mut()?;
+        match key.code {
+            Esc => {
+                let prompt = self.prompt.take()?;
+                (self.row, self.col, self.scroll) = prompt.origin;
+                self.search = prompt.previous;
+                return None;
+            }
+            Enter => {
+                let prompt = self.prompt.take()?;
+                if prompt.input.is_empty() {
+                    self.search = prompt.previous;
+                } else if self.search.is_some() && self.matches().is_empty() {
+                    self.status = Some(format!("pattern not found: {}", prompt.input));
+                }
+                self.backward = prompt.backward;
+                return None;
+            }
+            Backspace => {
+                prompt.input.pop();
+            }
+            Char(c) => prompt.input.push(c),
+            _ => return None,
+        }
+        let (input, backward, origin) =
+            (regex::escape(&prompt.input), prompt.backward, prompt.origin);
+        (self.row, self.col, self.scroll) = origin;
+        self.search = Pattern::new(&input, search::Mode::Regex).ok();
+        if !self.jump(backward, false) {
+            // Wrap around within the chapter while typing.
+            (self.row, self.col) = if backward {
+                (usize::MAX, usize::MAX)
+            } else {
+                (0, 0)
+            };
+            if !self.jump(backward, true) {
+                (self.row, self.col, self.scroll) = origin;
+            }
+        }
+        None
+    }
+
+    fn naming_key(&mut self, key: crossterm::event::KeyEvent) -> Option<Action> {
+        use crossterm::event::KeyCode::*;
+        let naming = self.naming.as_mut()?;
+        let input = if naming.folder {
+            &mut naming.bookmark.folder
+        } else {
+            &mut naming.bookmark.label
+        };
+        match key.code {
+            Esc => self.naming = None,
+            Enter => {
+                let mut bookmark = self.naming.take()?.bookmark;
+                bookmark.label = bookmark.label.trim().to_owned();
+                bookmark.folder = bookmark.folder.trim().to_owned();
+                self.status = Some(format!(
+                    "bookmarked {} {}:{}",
+                    bookmark.book, bookmark.chapter, bookmark.verse
+                ));
+                return Some(Action::SaveBookmark(bookmark));
             }
+            Tab | BackTab => naming.folder = !naming.folder,
+            Backspace => {
+                input.pop();
+            }
+            Char(c) => input.push(c),
+            _ => {}
+        }
+        None
+    }
+
+    fn tagging_key(&mut self, key: crossterm::event::KeyEvent) -> Option<Action> {
+        use crossterm::event::KeyCode::*;
+        let tagging = self.tagging.as_mut()?;
+        match key.code {
+            Esc => self.tagging = None,
+            Enter => {
+                let mut tag = self.tagging.take()?;
+                tag.name = tags::normalize(&tag.name);
+                if tag.name.is_empty() {
+                    return None;
+                }
+                let reference = tag.reference();
+                // The same tag again on the same verses takes it off.
+                if self
+                    .tags
+                    .get(&tag.name, &tag.book, tag.chapter, tag.verse, tag.end)
+                    .is_some()
+                {
+                    self.status = Some(format!("removed #{} from {reference}", tag.name));
+                    return Some(Action::DeleteTag(tag));
+                }
+                self.status = Some(format!("tagged {reference} #{}", tag.name));
+                return Some(Action::SaveTag(tag));
+            }
+            // Complete to the first tag in use that starts with the input.
+            Tab => {
+                let typed = tags::normalize(&tagging.name);
+                if let Some((name, _)) = self
+                    .tags
+                    .names()
+                    .into_iter()
+                    .find(|(name, _)| name.starts_with(&typed))
+                {
+                    tagging.name = name;
+                }
+            }
+            Backspace => {
+                tagging.name.pop();
+            }
+            Char(c) => tagging.name.push(c),
+            _ => {}
         }
+        None
+    }
+
+    /// First and last verse of the visual selection, or the verse at the
+    /// cursor.
+    fn selected_verses(&self) -> Option<(u16, u16)> {
+        let verses = self.bible.passage(&self.book, self.chapter);
+        let range = self.normalized_range();
+        let verse = |row: Option<&(usize, usize, usize)>| {
+            row.and_then(|&(r, _, _)| verses.get(r)).map(|v| v.verse)
+        };
+        Some((verse(range.first())?, verse(range.last())?))
+    }
+
+    /// Colours named by the `highlight_` styles, sorted.
+    fn colours(&self) -> Vec<&str> {
+        let mut colours: Vec<&str> = self
+            .styles
+            .keys()
+            .filter_map(|k| k.strip_prefix(STYLE_PREFIX))
+            .collect();
+        colours.sort_unstable();
+        colours
+    }
+
+    /// The visual selection of the focused column, or the verse at the
+    /// cursor, as a highlight yet to be given a colour.
+    fn selection(&self) -> Option<Highlight> {
+        let verses = self.bible.passage(&self.book, self.chapter);
+        let range = self.normalized_range();
+        let &(first, start, _) = range.first()?;
+        let &(last, _, end) = range.last()?;
+        let translation = match self.column.checked_sub(1) {
+            Some(c) => &self.parallel[c].0,
+            None => &self.translation,
+        };
+        Some(Highlight {
+            translation: translation.clone(),
+            book: self.book.clone(),
+            chapter: self.chapter,
+            start: (verses.get(first)?.verse, start),
+            end: (verses.get(last)?.verse, end),
+            colour: String::new(),
+            created: store::now(),
+        })
+    }
+
+    /// The notes on `verse`, in the pane beside the text.
+    fn draw_notes(&self, f: &mut Frame, area: Rect, verse: Option<u16>) {
+        let mut text = Text::default();
+        let title = match verse {
+            Some(v) => format!("Notes · {} {}:{v}", self.book, self.chapter),
+            None => "Notes".to_owned(),
+        };
+        let notes = verse.map_or(Vec::new(), |v| {
+            self.notes.on(&self.book, self.chapter, v).collect()
+        });
+        for note in &notes {
+            if !text.lines.is_empty() {
+                text.lines.push(Line::default());
+            }
+            text.lines.push(Line::from(note.reference()).bold());
+            text.lines.extend(markdown(&note.body).lines);
+        }
+        if notes.is_empty() {
+            text = Text::from("No note — e to write one").dim();
+        }
+        f.render_widget(
+            Paragraph::new(text)
+                .block(
+                    Block::default()
+                        .title(title)
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(Color::DarkGray)),
+                )
+                .wrap(Wrap { trim: false }),
+            area,
+        );
+    }
+
+    fn cur_line_len(&self) -> usize {
+        self.row_text(self.row).map_or(0, graphemes::len)
+    }
+
+    /// Whether the open chapter, `verses`, is laid out as prose. It needs
+    /// paragraph or poetry markers from the source, and a single column,
+    /// since parallel columns are aligned verse by verse.
+    fn in_prose(&self, verses: &[&Verse]) -> bool {
+        self.prose && self.parallel.is_empty() && verses.iter().any(|v| !v.breaks.is_empty())
+    }
+
+    /// Keep the cursor and visual anchor inside their rows after the focused
+    /// column changed.
+    fn clamp_cols(&mut self) {
+        let last = |len: usize| len.saturating_sub(1);
+        self.col = self.col.min(last(self.cur_line_len()));
+        self.anchor_col = self.anchor_col.min(last(
+            self.row_text(self.anchor_row).map_or(0, graphemes::len),
+        ));
     }
 
     fn normalized_range(&self) -> Vec<(usize, usize, usize)> {
@@ -67,33 +587,477 @@ impl Reader {
         };
         let mut out = Vec::new();
         for r in ar..=br {
-            let text_len = self
-                .bible
-                .passage(&self.book, self.chapter)
-                .get(r)
-                .map(|v| v.text.len())
-                .unwrap_or(0);
+            let text_len = self.row_text(r).map_or(0, graphemes::len);
             let s = if r == ar { ac } else { 0 };
             let e = if r == br { bc + 1 } else { text_len };
             out.push((r, s, e));
         }
         out
     }
+
+    fn style(&self, name: &str) -> Style {
+        self.styles.get(name).copied().unwrap_or_default()
+    }
+
+    /// Whether the first parallel column's changes to the main translation
+    /// are marked.
+    fn comparing(&self) -> bool {
+        self.compare && !self.parallel.is_empty()
+    }
+
+    /// Grapheme columns of row `i`, `len` long, under the cursor or selection.
+    /// The cursor and exact selection show in the focused column; the others
+    /// highlight whole rows of a visual selection.
+    fn selected_cols(&self, column: usize, i: usize, len: usize) -> Option<Range<usize>> {
+        let (ar, ac, br, bc) = if !self.visual {
+            (self.row, self.col, self.row, self.col)
+        } else if (self.anchor_row, self.anchor_col) <= (self.row, self.col) {
+            (self.anchor_row, self.anchor_col, self.row, self.col)
+        } else {
+            (self.row, self.col, self.anchor_row, self.anchor_col)
+        };
+        if i < ar || i > br {
+            None
+        } else if column == self.column {
+            let start = if i == ar { ac } else { 0 };
+            let end = if i == br { bc + 1 } else { len };
+            Some(start..end)
+        } else if self.visual {
+            Some(0..len)
+        } else {
+            None
+        }
+    }
+
+    /// Markers beside the number of `verse` of the open chapter.
+    fn gutter(&self, verse: u16) -> Vec<Span<'static>> {
+        let mut spans = Vec::new();
+        if self
+            .bookmarks
+            .get(&self.book, self.chapter, verse)
+            .is_some()
+        {
+            spans.push(Span::styled("▸", self.style("bookmark")));
+        }
+        if self
+            .notes
+            .on(&self.book, self.chapter, verse)
+            .next()
+            .is_some()
+        {
+            spans.push(Span::styled("✎", self.style("note")));
+        }
+        if self
+            .tags
+            .on(&self.book, self.chapter, verse)
+            .next()
+            .is_some()
+        {
+            spans.push(Span::styled("#", self.style("tag")));
+        }
+        spans
+    }
+
+    /// What is drawn over `verse` in column `c`, in order: highlights, the
+    /// words the compared translation changes, the goto passage, search
+    /// matches, and the cursor or selection. `main` and `other` are the same
+    /// verse in the main and first parallel column, and `row` the main
+    /// column's row it is on.
+    fn marks(
+        &self,
+        c: usize,
+        verse: &Verse,
+        (main, other): (Option<&Verse>, Option<&Verse>),
+        row: Option<usize>,
+        highlights: &[&Highlight],
+    ) -> Marks {
+        let len = graphemes::len(&verse.text);
+        let mut marks = Vec::new();
+        for h in highlights {
+            if let Some(cols) = h.columns(verse.verse, len) {
+                let style = self.style(&format!("{STYLE_PREFIX}{}", h.colour));
+                marks.push((graphemes::range(&verse.text, cols), style));
+            }
+        }
+        if self.comparing() && c < 2 {
+            let (added, removed) = (self.style("diff_added"), self.style("diff_removed"));
+            match (main, other) {
+                (Some(main), Some(other)) => {
+                    let d = diff::words(&main.text, &other.text);
+                    let (ranges, style) = if c == 0 {
+                        (d.removed, removed)
+                    } else {
+                        (d.added, added)
+                    };
+                    marks.extend(ranges.into_iter().map(|r| (r, style)));
+                }
+                _ if c == 0 => marks.push((0..verse.text.len(), removed)),
+                _ => marks.push((0..verse.text.len(), added)),
+            }
+        }
+        if let Some((first, last)) = self.passage
+            && (first..=last).contains(&verse.verse)
+        {
+            marks.push((0..verse.text.len(), self.style("passage")));
+        }
+        if let Some(pattern) = &self.search {
+            let matched = self.style("search_match");
+            marks.extend(
+                pattern
+                    .matches(&verse.text)
+                    .into_iter()
+                    .map(|r| (r, matched)),
+            );
+        }
+        if let Some(r) = row.and_then(|i| self.selected_cols(c, i, len)) {
+            marks.push((graphemes::range(&verse.text, r), CURSOR));
+        }
+        marks
+    }
+
+    /// The open chapter in column `c`, a line per row of `rows` with its
+    /// marks, or paragraphs in prose, then its footnotes; in prose, also the
+    /// line the cursor is on.
+    fn chapter_lines<'a>(
+        &self,
+        c: usize,
+        columns: &[Column<'a>],
+        passages: &[Vec<&'a Verse>],
+        rows: &[(u16, Option<usize>)],
+    ) -> (Vec<Line<'a>>, Option<usize>) {
+        let (abbrev, bible, _) = columns[c];
+        let (main, passage) = (&passages[0], &passages[c]);
+        let find =
+            |passage: &[&'a Verse], verse: u16| passage.iter().find(|p| p.verse == verse).copied();
+        let highlights: Vec<_> = self
+            .highlights
+            .in_chapter(abbrev, &self.book, self.chapter)
+            .collect();
+        let marked = rows.iter().map(|&(verse, i)| {
+            let aligned = match i {
+                Some(i) if c == 0 => Some(main[i]),
+                _ => find(passage, verse),
+            };
+            let Some(a) = aligned else {
+                return Err(Line::from(format!("{verse:>3} ")).style(Style::default().dim()));
+            };
+            let pair = (
+                i.map(|i| main[i]),
+                passages.get(1).and_then(|p| find(p, verse)),
+            );
+            Ok((a, self.marks(c, a, pair, i, &highlights)))
+        });
+        let (mut lines, cursor_line) = if self.in_prose(main) {
+            let marked: Vec<_> = marked.filter_map(Result::ok).collect();
+            let col = main
+                .get(self.row)
+                .map_or(0, |v| graphemes::byte(&v.text, self.col));
+            let (lines, line) = prose_lines(&marked, self.row, col, |v| self.gutter(v));
+            (lines, Some(line))
+        } else {
+            let lines = marked
+                .map(|l| {
+                    l.map_or_else(
+                        |line| line,
+                        |(a, marks)| verse_line(a, &marks, self.gutter(a.verse)),
+                    )
+                })
+                .collect();
+            (lines, None)
+        };
+        let notes = bible.footnotes(&self.book, self.chapter);
+        if !notes.is_empty() {
+            lines.push(Line::default());
+            for n in notes {
+                lines.push(
+                    Line::from(format!("{:>3}{} {}", n.verse, n.caller, n.text))
+                        .style(Style::default().dim()),
+                );
+            }
+        }
+        (lines, cursor_line)
+    }
+
+    /// The heading a chapter opens with in continuous mode, under the name
+    /// of its book if it is the book's first.
+    fn heading(&self, book: &str, chapter: u16) -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+        let opens_book = self
+            .chapters
+            .iter()
+            .find(|(b, _)| b == book)
+            .is_some_and(|(_, c)| *c == chapter);
+        if opens_book {
+            lines.push(Line::from(book.to_owned()).bold().centered());
+        }
+        lines.push(Line::from(format!("Chapter {chapter}")).bold().centered());
+        lines.push(Line::default());
+        lines
+    }
+
+    /// A neighbouring chapter in column `c` under its heading, without the
+    /// marks of the open one; `passages` are its verses in every column.
+    fn neighbour_lines<'a>(
+        &self,
+        c: usize,
+        prose: bool,
+        (book, chapter): &(String, u16),
+        passages: &[Vec<&'a Verse>],
+    ) -> Vec<Line<'a>> {
+        let mut lines = self.heading(book, *chapter);
+        if prose {
+            let marked: Vec<_> = passages[0].iter().map(|v| (*v, Marks::new())).collect();
+            lines.extend(prose_lines(&marked, usize::MAX, 0, |_| Vec::new()).0);
+        } else {
+            lines.extend(parallel_rows(passages).into_iter().map(|(verse, _)| {
+                match passages[c].iter().find(|p| p.verse == verse) {
+                    Some(a) => verse_line(a, &[], Vec::new()),
+                    None => Line::from(format!("{verse:>3} ")).style(Style::default().dim()),
+                }
+            }));
+        }
+        lines
+    }
+
+    /// Reading straight through, put the chapters before and after the open
+    /// one around its lines in every column.
+    fn surround<'a>(
+        &self,
+        columns: &[Column<'a>],
+        passages: &[Vec<&'a Verse>],
+        texts: &mut [Vec<Line<'a>>],
+    ) -> Around {
+        let mut around = Around::default();
+        if !self.continuous {
+            return around;
+        }
+        let prose = self.in_prose(&passages[0]);
+        let here = self
+            .chapters
+            .iter()
+            .position(|(b, c)| *b == self.book && *c == self.chapter);
+        // A neighbouring chapter, with its verses in every column.
+        let neighbour = |delta: isize| {
+            let (book, chapter) = here
+                .and_then(|i| i.checked_add_signed(delta))
+                .and_then(|i| self.chapters.get(i))?;
+            let passages: Vec<_> = columns
+                .iter()
+                .map(|(_, bible, _)| bible.passage(book, *chapter))
+                .collect();
+            Some(((book.clone(), *chapter), passages))
+        };
+        let (previous, next) = (neighbour(-1), neighbour(1));
+        for (c, lines) in texts.iter_mut().enumerate() {
+            let mut all = Vec::new();
+            if let Some((place, passages)) = &previous {
+                all.extend(self.neighbour_lines(c, prose, place, passages));
+                all.push(Line::default());
+            }
+            around.start = all.len();
+            all.extend(self.heading(&self.book, self.chapter));
+            around.opening = all.len() - around.start;
+            all.append(lines);
+            if let Some((place, passages)) = &next {
+                all.push(Line::default());
+                around.after = Some(all.len());
+                all.extend(self.neighbour_lines(c, prose, place, passages));
+            }
+            *lines = all;
+        }
+        around.previous = previous.map(|(place, _)| place);
+        around.next = next.map(|(place, _)| place);
+        around
+    }
+
+    /// Scroll so that the `cursor` line stays `scrolloff` lines inside a view
+    /// `height` lines tall. Going on into a neighbouring chapter, the
+    /// chapter left stays where it was on screen. `line_at` is the screen
+    /// line a line of the text starts on.
+    fn follow(
+        &mut self,
+        cursor: usize,
+        height: usize,
+        around: &Around,
+        line_at: impl Fn(usize) -> usize,
+    ) {
+        if let Some((book, chapter, row)) = self.shown.take()
+            && (book != self.book || chapter != self.chapter)
+        {
+            let left = Some((book, chapter));
+            let line = if left == around.previous {
+                Some(0)
+            } else if left == around.next {
+                around.after.map(&line_at)
+            } else {
+                None
+            };
+            if let Some(line) = line {
+                self.scroll = (line as i64 - row).max(0) as u16;
+            }
+        }
+        let margin = self.scrolloff.min(height.saturating_sub(1) / 2);
+        if cursor < self.scroll as usize + margin {
+            self.scroll = cursor.saturating_sub(margin) as u16;
+        } else if cursor + margin >= self.scroll as usize + height {
+            self.scroll = (cursor + margin + 1).saturating_sub(height) as u16;
+        }
+        if self.continuous {
+            self.shown = Some((
+                self.book.clone(),
+                self.chapter,
+                line_at(around.start) as i64 - self.scroll as i64,
+            ));
+        }
+    }
+
+    /// The line under the focused column: a prompt being typed, the colours
+    /// to highlight in, or the last message.
+    fn footer(&self) -> Option<Line<'_>> {
+        if let Some(prompt) = &self.prompt {
+            let lead = if prompt.backward { '?' } else { '/' };
+            Some(Line::from(format!("{lead}{}", prompt.input)))
+        } else if self.choosing {
+            let mut spans = vec![Span::raw("highlight ")];
+            for (i, colour) in self.colours().into_iter().enumerate() {
+                spans.push(Span::raw(format!(" {} ", i + 1)));
+                spans.push(Span::styled(
+                    colour,
+                    self.style(&format!("{STYLE_PREFIX}{colour}")),
+                ));
+            }
+            spans.push(Span::raw("  x erase"));
+            Some(Line::from(spans))
+        } else if let Some(naming) = &self.naming {
+            let field = |name: &str, input: &str, active: bool| {
+                let mut spans = vec![Span::raw(format!("{name}: {input}"))];
+                if active {
+                    spans.push(Span::raw(" ").reversed());
+                }
+                spans
+            };
+            let b = &naming.bookmark;
+            let mut spans = field("label", &b.label, !naming.folder);
+            spans.push(Span::raw("  "));
+            spans.extend(field("folder", &b.folder, naming.folder));
+            Some(Line::from(spans))
+        } else if let Some(tag) = &self.tagging {
+            Some(Line::from(vec![
+                Span::raw(format!("tag {} #{}", tag.reference(), tag.name)),
+                Span::raw(" ").reversed(),
+                Span::raw("  Tab complete"),
+            ]))
+        } else {
+            self.status
+                .as_deref()
+                .map(|status| Line::from(status).red())
+        }
+    }
 }
 
 impl Component for Reader {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.styles = config.styles.get(&Mode::Home).cloned().unwrap_or_default();
+        self.scrolloff = config.config.scrolloff.unwrap_or(3);
+        Ok(())
+    }
+
+    fn register_parallel_handler(&mut self, columns: &[(Translation, Arc<Bible>)]) -> Result<()> {
+        self.parallel = columns
+            .iter()
+            .map(|(t, bible)| (t.abbrev.clone(), bible.clone(), t.direction()))
+            .collect();
+        self.column = self.column.min(self.parallel.len());
+        self.clamp_cols();
+        Ok(())
+    }
+
+    fn register_bookmarks_handler(&mut self, bookmarks: &Bookmarks) -> Result<()> {
+        self.bookmarks = bookmarks.clone();
+        Ok(())
+    }
+
+    fn register_highlights_handler(&mut self, highlights: &Highlights) -> Result<()> {
+        self.highlights = highlights.clone();
+        Ok(())
+    }
+
+    fn register_notes_handler(&mut self, notes: &Notes) -> Result<()> {
+        self.notes = notes.clone();
+        Ok(())
+    }
+
+    fn register_tags_handler(&mut self, tags: &Tags) -> Result<()> {
+        self.tags = tags.clone();
+        Ok(())
+    }
+
+    /// Swap in the new text, staying on the same verse or the nearest one it has.
+    fn register_bible_handler(
+        &mut self,
+        bible: Arc<Bible>,
+        translation: &Translation,
+    ) -> Result<()> {
+        let verse = self
+            .bible
+            .passage(&self.book, self.chapter)
+            .get(self.row)
+            .map_or(1, |v| v.verse);
+        self.bible = bible;
+        self.chapters = self.bible.chapters();
+        self.translation = translation.abbrev.clone();
+        self.direction = translation.direction();
+        self.visual = false;
+        self.col = 0;
+        let Some((book, chapter, verse)) = self.bible.nearest(&self.book, self.chapter, verse)
+        else {
+            return Ok(());
+        };
+        self.row = self
+            .bible
+            .passage(&book, chapter)
+            .iter()
+            .position(|v| v.verse == verse)
+            .unwrap_or(0);
+        self.book = book.clone();
+        self.chapter = chapter;
+        if let Some(tx) = &self.action_tx {
+            tx.send(Action::OpenPassage { book, chapter })?;
+        }
+        Ok(())
+    }
+
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
         match action {
-            Action::OpenPassage { book, chapter } => {
-                self.book = book;
-                self.chapter = chapter;
-                self.row = 0;
-                self.col = 0;
-                self.scroll = 0;
-                self.visual = false;
+            Action::OpenPassage { book, chapter }
+                if book != self.book || chapter != self.chapter =>
+            {
+                self.open(book, chapter);
             }
             Action::MoveRow(dy) => {
                 let total = self.bible.passage(&self.book, self.chapter).len() as i32;
+                if total == 0 {
+                    return Ok(None);
+                }
+                if self.continuous && dy > 0 && self.row as i32 + 1 >= total {
+                    self.step_chapter(1)?;
+                    return Ok(None);
+                }
+                if self.continuous && dy < 0 && self.row == 0 {
+                    if self.step_chapter(-1)? {
+                        self.row = self
+                            .bible
+                            .passage(&self.book, self.chapter)
+                            .len()
+                            .saturating_sub(1);
+                    }
+                    return Ok(None);
+                }
                 let new_row = ((self.row as i32 + dy).clamp(0, total - 1)) as usize;
 
                 if dy > 0 && new_row > self.row && self.col >= self.cur_line_len() {
@@ -103,15 +1067,16 @@ impl Component for Reader {
                     self.row = new_row;
                     self.col = self.cur_line_len().saturating_sub(1);
                 } else {
+                    // Stay in the same screen column, which differs from the
+                    // character count where wide or combining characters are.
+                    let cells = self
+                        .row_text(self.row)
+                        .map_or(0, |text| graphemes::width(text, self.col));
                     self.row = new_row;
-                    let line_len = self.cur_line_len();
-                    if line_len == 0 {
-                        self.col = 0;
-                    } else {
-                        self.col = self.col.min(line_len.saturating_sub(1));
-                    }
+                    self.col = self
+                        .row_text(self.row)
+                        .map_or(0, |text| graphemes::col_at_width(text, cells));
                 }
-                self.ensure_visible();
             }
             Action::MoveCol(dx) => {
                 let line_len = self.cur_line_len();
@@ -123,7 +1088,8 @@ impl Component for Reader {
                     } else if self.row + 1 < verses.len() {
                         self.row += 1;
                         self.col = 0;
-                        self.ensure_visible();
+                    } else if self.continuous {
+                        self.step_chapter(1)?;
                     }
                 } else if dx < 0 {
                     if self.col > 0 {
@@ -132,10 +1098,114 @@ impl Component for Reader {
                         self.row -= 1;
                         let prev_line_len = self.cur_line_len();
                         self.col = prev_line_len.saturating_sub(1);
-                        self.ensure_visible();
+                    } else if self.continuous && self.step_chapter(-1)? {
+                        self.row = self
+                            .bible
+                            .passage(&self.book, self.chapter)
+                            .len()
+                            .saturating_sub(1);
+                        self.col = self.cur_line_len().saturating_sub(1);
+                    }
+                }
+            }
+            Action::SelectVerse { verse, end } => {
+                self.passage = end.map(|end| (verse, end));
+                let verses = self.bible.passage(&self.book, self.chapter);
+                self.row = verses.iter().rposition(|v| v.verse <= verse).unwrap_or(0);
+                self.col = 0;
+                self.visual = false;
+            }
+            Action::MarkMatches { query, mode } => self.search = Pattern::new(&query, mode).ok(),
+            Action::ToggleBookmark => {
+                let Some(verse) = self
+                    .bible
+                    .passage(&self.book, self.chapter)
+                    .get(self.row)
+                    .map(|v| v.verse)
+                else {
+                    return Ok(None);
+                };
+                let (book, chapter) = (self.book.clone(), self.chapter);
+                if self.bookmarks.get(&book, chapter, verse).is_some() {
+                    self.status = Some(format!("removed the bookmark on {book} {chapter}:{verse}"));
+                    return Ok(Some(Action::DeleteBookmark {
+                        book,
+                        chapter,
+                        verse,
+                    }));
+                }
+                self.naming = Some(Naming {
+                    bookmark: Bookmark::new(book, chapter, verse),
+                    folder: false,
+                });
+            }
+            Action::HighlightSelection(colour) => {
+                let Some(selection) = self.selection() else {
+                    return Ok(None);
+                };
+                self.visual = false;
+                if !colour.is_empty() {
+                    return Ok(Some(Action::SaveHighlight(Highlight {
+                        colour,
+                        ..selection
+                    })));
+                }
+                if let Some(tx) = &self.action_tx {
+                    for h in self
+                        .highlights
+                        .list
+                        .iter()
+                        .filter(|h| h.overlaps(&selection))
+                    {
+                        tx.send(Action::DeleteHighlight(h.clone()))?;
                     }
                 }
             }
+            Action::EditNote => {
+                let Some((first, last)) = self.selected_verses() else {
+                    return Ok(None);
+                };
+                let (book, chapter) = (self.book.as_str(), self.chapter);
+                // A selection is written on as a whole; the cursor alone
+                // opens whichever note its verse is in.
+                let existing = if self.visual {
+                    self.notes.get(book, chapter, first, last)
+                } else {
+                    self.notes.on(book, chapter, first).next()
+                };
+                let note = existing
+                    .cloned()
+                    .unwrap_or_else(|| Note::new(book.to_owned(), chapter, first, last));
+                self.visual = false;
+                return Ok(Some(Action::OpenNote(note)));
+            }
+            Action::TagSelection => {
+                let Some((first, last)) = self.selected_verses() else {
+                    return Ok(None);
+                };
+                self.tagging = Some(Tag::new("", self.book.clone(), self.chapter, first, last));
+            }
+            Action::ToggleNotePane => self.note_pane = !self.note_pane,
+            Action::Error(e) => self.status = Some(e),
+            Action::ToggleCompare => self.compare = !self.compare,
+            Action::ToggleContinuous => self.continuous = !self.continuous,
+            Action::ToggleProse => {
+                self.prose = !self.prose;
+                self.scroll = 0;
+            }
+            Action::NextChapter => {
+                self.step_chapter(1)?;
+            }
+            Action::PrevChapter => {
+                self.step_chapter(-1)?;
+            }
+            Action::NextBook => self.step_book(1)?,
+            Action::PrevBook => self.step_book(-1)?,
+            Action::FocusColumn(dx) => {
+                let last = self.parallel.len() as i32;
+                self.column = (self.column as i32 + dx).clamp(0, last) as usize;
+                self.clamp_cols();
+            }
             Action::ToggleVisual => {
                 self.visual = !self.visual;
                 if self.visual {
@@ -147,12 +1217,17 @@ impl Component for Reader {
                 let range = self.normalized_range();
                 let mut buf = String::new();
                 for (r, s, e) in range {
-                    if let Some(v) = self.bible.passage(&self.book, self.chapter).get(r) {
-                        buf.push_str(&v.text[s.min(v.text.len())..e.min(v.text.len())]);
+                    if let Some(text) = self.row_text(r) {
+                        buf.push_str(&text[graphemes::range(text, s..e)]);
                         buf.push('\n');
                     }
                 }
-                self.clipboard.set_text(buf.trim_end())?;
+                if self.clipboard.is_none() {
+                    self.clipboard = Some(Clipboard::new()?);
+                }
+                if let Some(clipboard) = &mut self.clipboard {
+                    clipboard.set_text(buf.trim_end())?;
+                }
                 self.visual = false;
             }
             _ => {}
@@ -160,71 +1235,839 @@ impl Component for Reader {
         Ok(None)
     }
 
+    fn captures_input(&self) -> bool {
+        self.prompt.is_some() || self.naming.is_some() || self.tagging.is_some()
+    }
+
     fn handle_key_event(&mut self, key: crossterm::event::KeyEvent) -> Result<Option<Action>> {
-        use crossterm::event::KeyCode::*;
+        use crossterm::event::{KeyCode::*, KeyModifiers};
+        if self.prompt.is_some() {
+            return Ok(self.prompt_key(key));
+        }
+        if self.naming.is_some() {
+            return Ok(self.naming_key(key));
+        }
+        if self.tagging.is_some() {
+            return Ok(self.tagging_key(key));
+        }
+        if self.choosing {
+            self.choosing = false;
+            return Ok(match key.code {
+                Char('x') => Some(Action::HighlightSelection(String::new())),
+                Char(c) => c
+                    .to_digit(10)
+                    .and_then(|d| self.colours().get((d as usize).checked_sub(1)?).copied())
+                    .map(|colour| Action::HighlightSelection(colour.to_owned())),
+                _ => None,
+            });
+        }
+        self.status = None;
         match key.code {
+            Char(c @ ('/' | '?')) => {
+                self.prompt = Some(Prompt {
+                    input: String::new(),
+                    backward: c == '?',
+                    origin: (self.row, self.col, self.scroll),
+                    previous: self.search.clone(),
+                });
+                Ok(None)
+            }
+            Char('n') => {
+                self.next_match(self.backward)?;
+                Ok(None)
+            }
+            Char('N') => {
+                self.next_match(!self.backward)?;
+                Ok(None)
+            }
+            Char('*') => {
+                self.search_word()?;
+                Ok(None)
+            }
             Char('j') | Down => Ok(Some(Action::MoveRow(1))),
             Char('k') | Up => Ok(Some(Action::MoveRow(-1))),
+            Char('h') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                Ok(Some(Action::FocusColumn(-1)))
+            }
+            Char('l') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                Ok(Some(Action::FocusColumn(1)))
+            }
+            Char('h') | Left if self.rtl_cursor() => Ok(Some(Action::MoveCol(1))),
+            Char('l') | Right if self.rtl_cursor() => Ok(Some(Action::MoveCol(-1))),
             Char('h') | Left => Ok(Some(Action::MoveCol(-1))),
             Char('l') | Right => Ok(Some(Action::MoveCol(1))),
             Char('v') => Ok(Some(Action::ToggleVisual)),
             Char('y') => Ok(Some(Action::Yank)),
+            Char('c') => Ok(Some(Action::ToggleCompare)),
+            Char('D') => Ok(Some(Action::ListDifferences)),
+            Char('b') => Ok(Some(Action::ToggleBookmark)),
+            Char('e') => Ok(Some(Action::EditNote)),
+            Char('E') => Ok(Some(Action::ToggleNotePane)),
+            Char('#') => Ok(Some(Action::TagSelection)),
+            Char('m') => {
+                if self.colours().is_empty() {
+                    self.status = Some(format!("no {STYLE_PREFIX} styles in the config"));
+                } else {
+                    self.choosing = true;
+                }
+                Ok(None)
+            }
+            Char('C') => Ok(self.cursor_word().map(Action::Concordance)),
+            Char('R') => Ok(Some(Action::ToggleContinuous)),
+            Char('P') => Ok(Some(Action::ToggleProse)),
             Esc => Ok(Some(Action::ToggleVisual)),
             _ => Ok(None),
         }
     }
 
     fn draw(&mut self, f: &mut Frame, area: Rect, focus: Focus) -> Result<()> {
-        let verses = self.bible.passage(&self.book, self.chapter);
+        // The texts are held apart from `self`, whose scroll is set once
+        // their lines are laid out.
+        let (bible, parallel, translation) = (
+            self.bible.clone(),
+            self.parallel.clone(),
+            self.translation.clone(),
+        );
+        let mut columns: Vec<Column> = vec![(&translation, &bible, self.direction)];
+        columns.extend(
+            parallel
+                .iter()
+                .map(|(t, b, d)| (t.as_str(), b.as_ref(), *d)),
+        );
+        let passages: Vec<_> = columns
+            .iter()
+            .map(|(_, bible, _)| bible.passage(&self.book, self.chapter))
+            .collect();
+
+        let area = if self.note_pane {
+            let [text, pane] =
+                Layout::horizontal([Constraint::Min(0), Constraint::Percentage(30)]).areas(area);
+            self.draw_notes(f, pane, passages[0].get(self.row).map(|v| v.verse));
+            text
+        } else {
+            area
+        };
+        let areas = Layout::horizontal(vec![
+            Constraint::Ratio(1, columns.len() as u32);
+            columns.len()
+        ])
+        .split(area);
 
-        let (ar, ac, br, bc) = if self.visual {
-            if (self.anchor_row, self.anchor_col) <= (self.row, self.col) {
-                (self.anchor_row, self.anchor_col, self.row, self.col)
+        let rows = parallel_rows(&passages);
+        // Rows only another column has come before some of the main
+        // column's verses, so the cursor is drawn on the row of its verse.
+        let cursor_row = rows
+            .iter()
+            .position(|(_, i)| *i == Some(self.row))
+            .unwrap_or_default();
+        let mut cursor_line = None;
+        let mut texts: Vec<Vec<Line>> = Vec::with_capacity(columns.len());
+        for c in 0..columns.len() {
+            let (lines, line) = self.chapter_lines(c, &columns, &passages, &rows);
+            cursor_line = line.or(cursor_line);
+            texts.push(lines);
+        }
+        // Columns with fewer footnotes are padded to keep what follows
+        // aligned.
+        let len = texts.iter().map(Vec::len).max().unwrap_or(0);
+        for lines in &mut texts {
+            lines.resize(len, Line::default());
+        }
+        let around = self.surround(&columns, &passages, &mut texts);
+
+        // Screen rows each line takes once wrapped; in parallel, the tallest
+        // of its row, to which every column is padded so they stay aligned
+        // however differently they wrap. Right-to-left lines are wrapped
+        // here rather than by ratatui, which only lays out left to right.
+        let rtl = |c: usize, line: &Line| bidi::is_rtl_line(line, columns[c].2);
+        let wrapped = |c: usize, line: &Line| {
+            let width = areas[c].width.saturating_sub(2);
+            if rtl(c, line) {
+                bidi::wrap(line, width).len()
             } else {
-                (self.row, self.col, self.anchor_row, self.anchor_col)
+                Paragraph::new(line.clone())
+                    .wrap(Wrap { trim: true })
+                    .line_count(width)
             }
+        };
+        let heights: Vec<usize> = (0..texts[0].len())
+            .map(|i| {
+                (0..texts.len())
+                    .map(|c| wrapped(c, &texts[c][i]))
+                    .max()
+                    .unwrap_or(1)
+            })
+            .collect();
+        let focused = if self.in_prose(&passages[0]) {
+            0
         } else {
-            (self.row, self.col, self.row, self.col)
+            self.column
         };
+        let cursor_line = around.start + around.opening + cursor_line.unwrap_or(cursor_row);
+        let within = texts[focused].get(cursor_line).map_or(0, |line| {
+            let width = areas[focused].width.saturating_sub(2);
+            if rtl(focused, line) {
+                let marked = |l: &Line| l.spans.iter().any(|s| s.style.bg == CURSOR.bg);
+                let lines = bidi::wrap(line, width);
+                let at = if self.cursor_last() {
+                    lines.iter().rposition(marked)
+                } else {
+                    lines.iter().position(marked)
+                };
+                at.unwrap_or(0)
+            } else {
+                cursor_offset(line, width, self.cursor_last())
+            }
+        });
+        let line_at = |i: usize| heights.iter().take(i).sum::<usize>();
+        let cursor = line_at(cursor_line) + within;
+        if columns.len() > 1 {
+            for (c, lines) in texts.iter_mut().enumerate() {
+                let mut padded = Vec::new();
+                for (line, height) in lines.drain(..).zip(&heights) {
+                    let own = wrapped(c, &line);
+                    padded.push(line);
+                    padded.extend((own..*height).map(|_| Line::default()));
+                }
+                *lines = padded;
+            }
+        }
+        self.follow(
+            cursor,
+            area.height.saturating_sub(2) as usize,
+            &around,
+            line_at,
+        );
 
-        let mut lines = Vec::with_capacity(verses.len());
-        for (i, v) in verses.iter().enumerate() {
-            let mut spans = vec![Span::raw(format!("{:>3} ", v.verse))];
+        for (c, ((abbrev, _, _), lines)) in columns.iter().zip(texts).enumerate() {
+            let width = areas[c].width.saturating_sub(2);
+            let lines: Vec<Line> = lines
+                .into_iter()
+                .flat_map(|line| {
+                    if rtl(c, &line) {
+                        bidi::wrap(&line, width)
+                    } else {
+                        vec![line]
+                    }
+                })
+                .collect();
 
-            if i < ar || i > br {
-                spans.push(Span::raw(&v.text));
+            let border_style = if focus == Focus::Reader && c == self.column {
+                Style::default().fg(Color::Green)
             } else {
-                let start = if i == ar { ac } else { 0 };
-                let end = if i == br { bc + 1 } else { v.text.len() };
-                let (p1, rest) = v.text.split_at(start.min(v.text.len()));
-                let (p2, p3) = rest.split_at((end - start).min(rest.len()));
-                spans.extend([
-                    Span::raw(p1),
-                    Span::styled(p2, Style::default().bg(Color::DarkGray)),
-                    Span::raw(p3),
-                ]);
+                Style::default()
+            };
+            let mut block = Block::default();
+            if c == self.column
+                && let Some(footer) = self.footer()
+            {
+                block = block.title_bottom(footer);
             }
-            lines.push(Line::from(spans));
+            let changes = if self.comparing() && c == 1 {
+                " · changes"
+            } else {
+                ""
+            };
+            let para = Paragraph::new(lines)
+                .block(
+                    block
+                        .title(format!(
+                            "{} {} · {abbrev}{changes}",
+                            self.book, self.chapter
+                        ))
+                        .borders(Borders::ALL)
+                        .border_style(border_style),
+                )
+                .scroll((self.scroll, 0))
+                .wrap(Wrap { trim: true });
+
+            f.render_widget(para, areas[c]);
         }
 
-        let border_style = if focus == Focus::Reader {
-            Style::default().fg(Color::Green)
-        } else {
-            Style::default()
+        Ok(())
+    }
+}
+
+/// Style of the cursor and of a visual selection.
+const CURSOR: Style = Style::new().bg(Color::DarkGray);
+
+/// Screen row of `line`, wrapped to `width`, that the cursor is drawn on;
+/// its last marked span when `last`, as at the end of a selection.
+fn cursor_offset(line: &Line, width: u16, last: bool) -> usize {
+    let marked = line.spans.iter().map(|s| s.style.bg == CURSOR.bg);
+    let at = if last {
+        marked.clone().rposition(|m| m)
+    } else {
+        marked.clone().position(|m| m)
+    };
+    let Some(at) = at else {
+        return 0;
+    };
+    let prefix = Line::from(line.spans[..=at].to_vec());
+    Paragraph::new(prefix)
+        .wrap(Wrap { trim: true })
+        .line_count(width)
+        .saturating_sub(1)
+}
+
+/// Byte ranges of a verse's text, each with the style drawn over it.
+type Marks = Vec<(Range<usize>, Style)>;
+
+/// A translation shown in a column: its abbreviation, text and direction.
+type Column<'a> = (&'a str, &'a Bible, Option<Direction>);
+
+/// Where the open chapter's lines are among its neighbours' in continuous
+/// mode: the first line of its heading, how many lines that takes, and the
+/// first line of the next chapter.
+#[derive(Default)]
+struct Around {
+    previous: Option<(String, u16)>,
+    next: Option<(String, u16)>,
+    start: usize,
+    opening: usize,
+    after: Option<usize>,
+}
+
+/// The rows of the view: every verse number any column has, in order, with
+/// the index of the main column's verse on it. A verse only another
+/// translation has gets a row of its own. Such rows are read-only: the
+/// cursor moves over the main column's verses and passes them by.
+fn parallel_rows(passages: &[Vec<&Verse>]) -> Vec<(u16, Option<usize>)> {
+    let Some((main, others)) = passages.split_first() else {
+        return Vec::new();
+    };
+    let mut rows: Vec<_> = main
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (v.verse, Some(i)))
+        .collect();
+    if others.is_empty() {
+        return rows;
+    }
+    for v in others.iter().flatten() {
+        if !rows.iter().any(|(verse, _)| *verse == v.verse) {
+            rows.push((v.verse, None));
+        }
+    }
+    rows.sort_by_key(|(verse, _)| *verse);
+    rows
+}
+
+/// One verse as a line, with styles applied to byte ranges of its text;
+/// later marks are drawn over earlier ones. `gutter` markers take the place
+/// of the padding before the verse number.
+fn verse_line<'a>(
+    v: &'a Verse,
+    marks: &[(Range<usize>, Style)],
+    gutter: Vec<Span<'a>>,
+) -> Line<'a> {
+    let width = 3usize.saturating_sub(gutter.iter().map(Span::width).sum());
+    let mut spans = gutter;
+    spans.push(Span::raw(format!("{:>width$} ", v.verse)));
+    // Keep the source layout visible: paragraph starts get a pilcrow
+    // and poetic lines are indented by their level.
+    match v.breaks.first().filter(|b| b.offset == 0).map(|b| b.kind) {
+        Some(BreakKind::Paragraph) => {
+            spans.push(Span::styled("¶ ", Style::default().dim()));
+        }
+        Some(BreakKind::Poetry(level)) => {
+            spans.push(Span::raw("  ".repeat(level as usize)));
+        }
+        None => {}
+    }
+    spans.extend(text_spans(&v.text, 0..v.text.len(), marks));
+    Line::from(spans)
+}
+
+/// Verses flowed into paragraphs, for the prose layout: a line starts at
+/// each paragraph or poetry marker of the source, poetic lines are indented
+/// by their level and verse numbers are raised, after the `gutter` markers
+/// of their verse. Also returns the line the cursor at `row`, `col` is on.
+fn prose_lines<'a>(
+    verses: &[(&'a Verse, Marks)],
+    row: usize,
+    col: usize,
+    gutter: impl Fn(u16) -> Vec<Span<'a>>,
+) -> (Vec<Line<'a>>, usize) {
+    let mut lines = Vec::new();
+    let mut current: Vec<Span<'a>> = Vec::new();
+    let mut cursor = 0;
+    for (i, (v, marks)) in verses.iter().enumerate() {
+        let len = v.text.len();
+        let mut cuts: Vec<(usize, Option<BreakKind>)> = v
+            .breaks
+            .iter()
+            .map(|b| (b.offset.min(len), Some(b.kind)))
+            .collect();
+        if cuts.first().is_none_or(|&(offset, _)| offset > 0) {
+            cuts.insert(0, (0, None));
+        }
+        for (k, &(start, kind)) in cuts.iter().enumerate() {
+            let end = cuts.get(k + 1).map_or(len, |&(offset, _)| offset);
+            match kind {
+                Some(kind) => {
+                    if !current.is_empty() {
+                        lines.push(Line::from(std::mem::take(&mut current)));
+                    }
+                    // Non-breaking spaces, which wrapping does not trim.
+                    let indent = match kind {
+                        BreakKind::Paragraph => 2,
+                        BreakKind::Poetry(level) => 4 * level as usize,
+                    };
+                    current.push(Span::raw("\u{a0}".repeat(indent)));
+                }
+                None if !current.is_empty() => current.push(Span::raw(" ")),
+                None => {}
+            }
+            if k == 0 {
+                current.extend(gutter(v.verse));
+                current.push(Span::styled(superscript(v.verse), Style::default().dim()));
+            }
+            if i == row && (k == 0 || start <= col) {
+                cursor = lines.len();
+            }
+            let text = &v.text[start..end];
+            let start = start + text.len() - text.trim_start().len();
+            current.extend(text_spans(&v.text, start..end, marks));
+        }
+    }
+    if !current.is_empty() {
+        lines.push(Line::from(current));
+    }
+    (lines, cursor)
+}
+
+/// `range` of `text` as spans, split wherever a mark starts or ends.
+fn text_spans<'a>(
+    text: &'a str,
+    range: Range<usize>,
+    marks: &[(Range<usize>, Style)],
+) -> Vec<Span<'a>> {
+    let mut cuts: Vec<usize> = marks
+        .iter()
+        .flat_map(|(r, _)| [r.start, r.end])
+        .chain([range.start, range.end])
+        .filter(|i| range.contains(i) || *i == range.end)
+        .filter(|&i| text.is_char_boundary(i))
+        .collect();
+    cuts.sort_unstable();
+    cuts.dedup();
+    cuts.windows(2)
+        .map(|w| {
+            let style = marks
+                .iter()
+                .filter(|(r, _)| r.start <= w[0] && w[1] <= r.end)
+                .fold(Style::default(), |acc, (_, s)| acc.patch(*s));
+            Span::styled(&text[w[0]..w[1]], style)
+        })
+        .collect()
+}
+
+/// A verse number in superscript digits.
+fn superscript(n: u16) -> String {
+    const DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+    n.to_string()
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(|d| DIGITS[d as usize])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::bible::Break;
+
+    fn verse(verse: u16, text: &str, breaks: &[(usize, BreakKind)]) -> Verse {
+        Verse {
+            book: "Psalms".into(),
+            chapter: 23,
+            verse,
+            text: text.into(),
+            breaks: breaks
+                .iter()
+                .map(|&(offset, kind)| Break { offset, kind })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_prose_lines() {
+        let verses = [
+            verse(
+                1,
+                "The LORD is my shepherd; I shall not want.",
+                &[(0, BreakKind::Poetry(1)), (24, BreakKind::Poetry(2))],
+            ),
+            verse(2, "He maketh me to lie down", &[]),
+            verse(3, "He restoreth my soul.", &[(0, BreakKind::Paragraph)]),
+        ];
+        let marked: Vec<_> = verses.iter().map(|v| (v, Vec::new())).collect();
+        let text = |row, col| {
+            let (lines, cursor) = prose_lines(&marked, row, col, |_| Vec::new());
+            let lines: Vec<String> = lines.iter().map(Line::to_string).collect();
+            (lines, cursor)
+        };
+        let (lines, cursor) = text(0, 0);
+        assert_eq!(
+            lines,
+            vec![
+                "\u{a0}\u{a0}\u{a0}\u{a0}¹The LORD is my shepherd;",
+                "\u{a0}\u{a0}\u{a0}\u{a0}\u{a0}\u{a0}\u{a0}\u{a0}I shall not want. ²He maketh me to lie down",
+                "\u{a0}\u{a0}³He restoreth my soul.",
+            ]
+        );
+        assert_eq!(cursor, 0);
+        assert_eq!(text(0, 30).1, 1);
+        assert_eq!(text(1, 3).1, 1);
+        assert_eq!(text(2, 0).1, 2);
+    }
+
+    #[test]
+    fn test_cursor_on_multibyte_text() {
+        let v = verse(1, "Ἐν ἀρχῇ ἦν ὁ λόγος", &[]);
+        let cursor = Style::default().reversed();
+        let line = |cols| verse_line(&v, &[(graphemes::range(&v.text, cols), cursor)], Vec::new());
+        let marked = |line: Line| {
+            line.spans
+                .iter()
+                .filter(|s| s.style == cursor)
+                .map(|s| s.content.to_string())
+                .collect::<Vec<_>>()
         };
+        assert_eq!(marked(line(4..5)), vec!["ρ"]);
+        assert_eq!(marked(line(6..9)), vec!["ῇ ἦ"]);
+        assert_eq!(marked(line(17..99)), vec!["ς"]);
+    }
 
-        let para = Paragraph::new(lines)
-            .block(
-                Block::default()
-                    .title(format!("{} {}", self.book, self.chapter))
-                    .borders(Borders::ALL)
-                    .border_style(border_style),
-            )
-            .scroll((self.scroll, 0))
-            .wrap(Wrap { trim: true });
+    /// A bible of `(book, chapter, verse texts)`, numbered from verse 1.
+    fn bible(chapters: &[(&str, u16, &[&str])]) -> Arc<Bible> {
+        let verses = chapters
+            .iter()
+            .flat_map(|&(book, chapter, texts)| {
+                texts.iter().enumerate().map(move |(i, text)| Verse {
+                    book: book.into(),
+                    chapter,
+                    verse: i as u16 + 1,
+                    text: (*text).into(),
+                    ..Default::default()
+                })
+            })
+            .collect();
+        let mut bible = Bible::default();
+        bible.verses = verses;
+        Arc::new(bible)
+    }
 
-        f.render_widget(para, area);
+    fn translation(abbrev: &str) -> Translation {
+        Translation {
+            abbrev: abbrev.into(),
+            ..Default::default()
+        }
+    }
 
-        Ok(())
+    /// A reader showing `bible`, at the verse of `book chapter` given.
+    fn reader(bible: Arc<Bible>, book: &str, chapter: u16, verse: u16) -> Reader {
+        let mut reader = Reader::new();
+        reader
+            .register_bible_handler(bible, &translation("A"))
+            .unwrap();
+        reader.open(book.into(), chapter);
+        reader
+            .update(Action::SelectVerse { verse, end: None })
+            .unwrap();
+        reader
+    }
+
+    fn position(reader: &Reader) -> (String, u16, u16) {
+        let verse = reader.bible.passage(&reader.book, reader.chapter)[reader.row].verse;
+        (reader.book.clone(), reader.chapter, verse)
+    }
+
+    #[test]
+    fn test_switch_translation() {
+        let a = bible(&[("John", 3, &["a", "b", "c", "d"]), ("John", 4, &["e"])]);
+        let mut reader = reader(a, "John", 3, 4);
+        reader.col = 1;
+
+        // The same verse when the new text has it...
+        let b = bible(&[("John", 3, &["A", "B", "C", "D", "E"])]);
+        reader.register_bible_handler(b, &translation("B")).unwrap();
+        assert_eq!(position(&reader), ("John".into(), 3, 4));
+        assert_eq!((reader.translation.as_str(), reader.col), ("B", 0));
+
+        // ...else the last one before it in the chapter...
+        let c = bible(&[("John", 3, &["A", "B"])]);
+        reader.register_bible_handler(c, &translation("C")).unwrap();
+        assert_eq!(position(&reader), ("John".into(), 3, 2));
+
+        // ...else the nearest chapter of the book, or the first chapter.
+        let d = bible(&[("John", 1, &["A"]), ("John", 5, &["B", "C"])]);
+        reader.register_bible_handler(d, &translation("D")).unwrap();
+        assert_eq!(position(&reader), ("John".into(), 1, 1));
+        let e = bible(&[("Genesis", 1, &["A"])]);
+        reader.register_bible_handler(e, &translation("E")).unwrap();
+        assert_eq!(position(&reader), ("Genesis".into(), 1, 1));
+    }
+
+    #[test]
+    fn test_move_in_empty_chapter() {
+        let mut reader = reader(bible(&[("John", 3, &["a"])]), "Jude", 1, 1);
+        reader.update(Action::MoveRow(1)).unwrap();
+        reader.update(Action::MoveRow(-1)).unwrap();
+        assert_eq!((reader.row, reader.col), (0, 0));
+    }
+
+    #[test]
+    fn test_search_across_chapters() {
+        let b = bible(&[
+            ("Genesis", 1, &["Light and dark", "waters"]),
+            ("Genesis", 2, &["dust", "again light"]),
+            ("Exodus", 1, &["names"]),
+        ]);
+        let mut reader = reader(b, "Genesis", 1, 1);
+        let key = |reader: &mut Reader, c| {
+            reader
+                .handle_key_event(crossterm::event::KeyCode::Char(c).into())
+                .unwrap();
+            (position(reader), reader.col, reader.status.clone())
+        };
+
+        // `*` searches the word under the cursor, on into the next chapter.
+        let at = |chapter, verse, col| (("Genesis".to_owned(), chapter, verse), col);
+        let (here, col, status) = key(&mut reader, '*');
+        assert_eq!(((here, col), status), (at(2, 2, 6), None));
+
+        // `n` wraps from the end of the bible, `N` from its start.
+        let (here, col, status) = key(&mut reader, 'n');
+        assert_eq!((here, col), at(1, 1, 0));
+        assert_eq!(
+            status.as_deref(),
+            Some("search hit BOTTOM, continuing at TOP")
+        );
+        let (here, col, status) = key(&mut reader, 'N');
+        assert_eq!((here, col), at(2, 2, 6));
+        assert_eq!(
+            status.as_deref(),
+            Some("search hit TOP, continuing at BOTTOM")
+        );
+
+        // A word found nowhere else leaves the cursor on it.
+        reader.open("Exodus".into(), 1);
+        let (here, col, _) = key(&mut reader, '*');
+        assert_eq!((here, col), (("Exodus".into(), 1, 1), 0));
+    }
+
+    /// The reader drawn on a `width` by `height` screen, a string per row.
+    fn screen(reader: &mut Reader, width: u16, height: u16) -> Vec<String> {
+        let mut terminal = Terminal::new(backend::TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|f| reader.draw(f, f.area(), Focus::Reader).unwrap())
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..height)
+            .map(|y| (0..width).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect()
+    }
+
+    /// Screen row each of `words` is first shown on.
+    fn rows_of(screen: &[String], words: &[&str]) -> Vec<Option<usize>> {
+        words
+            .iter()
+            .map(|w| screen.iter().position(|row| row.contains(w)))
+            .collect()
+    }
+
+    #[test]
+    fn test_step_chapters() {
+        let b = bible(&[
+            ("Genesis", 1, &["a", "b"]),
+            ("Genesis", 2, &["c", "d"]),
+            ("Exodus", 1, &["e"]),
+            ("Exodus", 2, &["f"]),
+        ]);
+        let mut reader = reader(b, "Genesis", 1, 2);
+        let mut step = |action| {
+            reader.update(action).unwrap();
+            position(&reader)
+        };
+        let at = |book: &str, chapter, verse| (book.to_owned(), chapter, verse);
+        assert_eq!(step(Action::NextChapter), at("Genesis", 2, 1));
+        assert_eq!(step(Action::NextChapter), at("Exodus", 1, 1));
+        assert_eq!(step(Action::PrevChapter), at("Genesis", 2, 1));
+        assert_eq!(step(Action::NextBook), at("Exodus", 1, 1));
+        assert_eq!(step(Action::NextBook), at("Exodus", 1, 1));
+        assert_eq!(step(Action::PrevBook), at("Genesis", 1, 1));
+        assert_eq!(step(Action::PrevChapter), at("Genesis", 1, 1));
+
+        // Moving past the ends of a chapter stays in it unless reading
+        // straight through.
+        assert_eq!(step(Action::MoveRow(-1)), at("Genesis", 1, 1));
+        step(Action::ToggleContinuous);
+        assert_eq!(step(Action::MoveRow(-1)), at("Genesis", 1, 1));
+        step(Action::MoveRow(1));
+        assert_eq!(step(Action::MoveRow(1)), at("Genesis", 2, 1));
+        assert_eq!(step(Action::MoveRow(-1)), at("Genesis", 1, 2));
+        assert_eq!(step(Action::MoveCol(1)), at("Genesis", 2, 1));
+        assert_eq!(step(Action::MoveCol(-1)), at("Genesis", 1, 2));
+    }
+
+    #[test]
+    fn test_continuous() {
+        let b = bible(&[
+            ("Genesis", 1, &["ALPHA", "BETA"]),
+            ("Genesis", 2, &["GAMMA", "DELTA"]),
+            ("Exodus", 1, &["EPSILON"]),
+        ]);
+        let mut reader = reader(b, "Genesis", 2, 1);
+        let words = [
+            "ALPHA",
+            "BETA",
+            "Chapter 2",
+            "GAMMA",
+            "DELTA",
+            "Exodus",
+            "EPSILON",
+        ];
+        assert_eq!(
+            rows_of(&screen(&mut reader, 40, 20), &words),
+            [None, None, None, Some(1), Some(2), None, None]
+        );
+
+        // The chapters around are in the same buffer, under their headings.
+        reader.update(Action::ToggleContinuous).unwrap();
+        let rows = rows_of(&screen(&mut reader, 40, 20), &words);
+        assert_eq!(rows, [4, 5, 7, 9, 10, 12, 15].map(Some));
+
+        // Reading on into the next chapter and back scrolls as through one
+        // text: back up, the cursor keeps its margin from the top.
+        reader.update(Action::MoveRow(1)).unwrap();
+        let rows =
+            |reader: &mut Reader| rows_of(&screen(reader, 40, 8), &["GAMMA", "DELTA", "EPSILON"]);
+        assert_eq!(rows(&mut reader), [Some(3), Some(4), None]);
+        reader.update(Action::MoveRow(1)).unwrap();
+        assert_eq!(position(&reader), ("Exodus".into(), 1, 1));
+        assert_eq!(rows(&mut reader), [None, None, Some(4)]);
+        reader.update(Action::MoveRow(-1)).unwrap();
+        assert_eq!(position(&reader), ("Genesis".into(), 2, 2));
+        assert_eq!(rows(&mut reader), [Some(2), Some(3), None]);
+    }
+
+    #[test]
+    fn test_scrolloff() {
+        let long = format!("ALPHA {}OMEGA", "word ".repeat(12));
+        let texts: Vec<String> = (1..=20)
+            .map(|v| match v {
+                13 => long.clone(),
+                v => format!("v{v}"),
+            })
+            .collect();
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let mut reader = reader(bible(&[("John", 1, &texts)]), "John", 1, 20);
+        screen(&mut reader, 20, 12);
+
+        // Scrolling up onto the wrapped verse keeps three lines above it...
+        reader
+            .update(Action::SelectVerse {
+                verse: 13,
+                end: None,
+            })
+            .unwrap();
+        let rows = rows_of(&screen(&mut reader, 20, 12), &["v10", "ALPHA", "OMEGA"]);
+        assert_eq!(rows[0].zip(rows[1]).map(|(a, b)| b - a), Some(3));
+        assert!(rows[2].is_some_and(|row| row > rows[1].unwrap() + 2));
+
+        // ...and with the cursor on its last screen line, three below it.
+        reader.col = graphemes::len(&long) - 1;
+        let rows = rows_of(&screen(&mut reader, 20, 12), &["OMEGA", "v16", "v17"]);
+        assert_eq!(rows[0].zip(rows[1]).map(|(a, b)| b - a), Some(3));
+        assert_eq!(rows[2], None);
+    }
+
+    #[test]
+    fn test_parallel_alignment() {
+        let main = bible(&[(
+            "John",
+            3,
+            &[
+                "alpha",
+                "beta is a verse long enough to wrap over several rows",
+                "gamma",
+                "delta",
+            ],
+        )]);
+        // No verse 2, a long verse 3, and a verse 5 the main text lacks.
+        let other = bible(&[(
+            "John",
+            3,
+            &[
+                "ALPHA",
+                "",
+                "GAMMA goes on for rows and rows as well",
+                "DELTA",
+                "EPSILON",
+            ],
+        )]);
+        let mut other = Arc::unwrap_or_clone(other);
+        other.verses.remove(1);
+        let mut reader = reader(main, "John", 3, 1);
+        reader
+            .register_parallel_handler(&[(translation("B"), Arc::new(other))])
+            .unwrap();
+
+        let screen = screen(&mut reader, 40, 20);
+        let left = rows_of(&screen, &["alpha", "beta", "gamma", "delta"]);
+        let right = rows_of(&screen, &["ALPHA", "GAMMA", "DELTA"]);
+        assert_eq!(right, [left[0], left[2], left[3]]);
+        // The right column is blank but for the number where it lacks verse
+        // 2, and is padded to each verse's tallest wrap on either side.
+        assert!(left[2].unwrap() > left[1].unwrap() + 1);
+        assert!(left[3].unwrap() > left[2].unwrap() + 1);
+        // Verse 5, which only the right column has, gets a row of its own
+        // under verse 4, with a blank numbered cell on the left.
+        let epsilon = rows_of(&screen, &["EPSILON"])[0].unwrap();
+        assert_eq!(epsilon, left[3].unwrap() + 1);
+        assert_eq!(screen[epsilon], "│5                 ││5 EPSILON         │");
+    }
+
+    #[test]
+    fn test_parallel_extra_rows() {
+        // The main text has only verses 1 and 20; the other has all twenty.
+        let texts: Vec<String> = (1..=20).map(|v| format!("v{v}")).collect();
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let mut main = Arc::unwrap_or_clone(bible(&[("John", 1, &texts)]));
+        main.verses.retain(|v| matches!(v.verse, 1 | 20));
+        let mut reader = reader(Arc::new(main), "John", 1, 1);
+        reader
+            .register_parallel_handler(&[(translation("B"), bible(&[("John", 1, &texts)]))])
+            .unwrap();
+
+        // The rows only the other column has are passed over...
+        let action = reader
+            .handle_key_event(crossterm::event::KeyCode::Char('j').into())
+            .unwrap();
+        reader.update(action.unwrap()).unwrap();
+        assert_eq!(position(&reader), ("John".into(), 1, 20));
+        // ...and the view scrolls to the row of the verse under the cursor,
+        // not to the cursor's index among the main column's verses.
+        let screen = screen(&mut reader, 40, 12);
+        assert_eq!(rows_of(&screen, &["│20 v20"]), [Some(7)]);
+    }
+
+    #[test]
+    fn test_text_spans() {
+        let text = "grace and peace";
+        let bold = Style::default().bold();
+        let spans = text_spans(text, 6..15, &[(0..9, bold), (10..99, bold)]);
+        let parts: Vec<_> = spans
+            .iter()
+            .map(|s| (s.content.as_ref(), s.style))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![("and", bold), (" ", Style::default()), ("peace", bold),]
+        );
     }
 }
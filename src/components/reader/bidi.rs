@@ -0,0 +1,247 @@
+//! Right-to-left layout. A terminal draws cells left to right in the order
+//! it is given them, so Hebrew or Arabic text is wrapped into lines in
+//! reading order here, and each line then reordered the way it is seen:
+//! right-to-left runs reversed, left-to-right runs such as numbers or Latin
+//! words kept in order within them, and brackets mirrored.
+
+use ratatui::{prelude::*, text::Line};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::library::Direction;
+
+/// One grapheme with its style.
+type Cell<'a> = (&'a str, Style);
+
+#[derive(Clone, Copy, PartialEq)]
+enum Class {
+    Left,
+    Right,
+    Neutral,
+}
+
+fn is_rtl(c: char) -> bool {
+    !c.is_numeric()
+        && matches!(c,
+            '\u{0590}'..='\u{08FF}'
+            | '\u{FB1D}'..='\u{FDFF}'
+            | '\u{FE70}'..='\u{FEFF}'
+            | '\u{10800}'..='\u{10FFF}'
+            | '\u{1E800}'..='\u{1EFFF}')
+}
+
+fn class(g: &str) -> Class {
+    match g.chars().next() {
+        Some(c) if is_rtl(c) => Class::Right,
+        Some(c) if c.is_alphanumeric() => Class::Left,
+        _ => Class::Neutral,
+    }
+}
+
+/// Direction of the first letter of `text` that has one.
+pub fn detect(text: &str) -> Option<Direction> {
+    text.chars().find_map(|c| {
+        if is_rtl(c) {
+            Some(Direction::Rtl)
+        } else if c.is_alphabetic() {
+            Some(Direction::Ltr)
+        } else {
+            None
+        }
+    })
+}
+
+/// Whether `line` is laid out right to left: as `direction` says, or as its
+/// text reads when that is unknown.
+pub fn is_rtl_line(line: &Line, direction: Option<Direction>) -> bool {
+    let direction = direction.or_else(|| line.spans.iter().find_map(|s| detect(&s.content)));
+    direction == Some(Direction::Rtl)
+}
+
+/// Whitespace a line may break at; a no-break space is kept as text, as it
+/// is when ratatui wraps.
+fn is_space(g: &str) -> bool {
+    g != "\u{a0}" && g.chars().all(char::is_whitespace)
+}
+
+/// Split `line` into lines at most `width` cells wide, breaking between
+/// words where it can and dropping the spaces at each break, then lay every
+/// line out right to left, aligned to the right edge.
+pub fn wrap(line: &Line, width: u16) -> Vec<Line<'static>> {
+    let width = width.max(1) as usize;
+    let cells: Vec<Cell> = line
+        .spans
+        .iter()
+        .flat_map(|s| s.content.graphemes(true).map(|g| (g, s.style)))
+        .collect();
+    let mut rows: Vec<Vec<Cell>> = Vec::new();
+    let (mut row, mut used): (Vec<Cell>, usize) = (Vec::new(), 0);
+    let mut rest = &cells[..];
+    while !rest.is_empty() {
+        let space = is_space(rest[0].0);
+        let n = rest
+            .iter()
+            .position(|c| is_space(c.0) != space)
+            .unwrap_or(rest.len());
+        let (token, tail) = rest.split_at(n);
+        rest = tail;
+        let cells_width = |cells: &[Cell]| cells.iter().map(|c| c.0.width()).sum::<usize>();
+        if space {
+            // Kept only if a word follows on the same line.
+            if used > 0 {
+                let next = rest
+                    .iter()
+                    .take_while(|c| !is_space(c.0))
+                    .map(|c| c.0.width())
+                    .sum::<usize>();
+                let w = cells_width(token);
+                if used + w + next <= width {
+                    row.extend_from_slice(token);
+                    used += w;
+                }
+            }
+            continue;
+        }
+        if used > 0 && used + cells_width(token) > width {
+            rows.push(std::mem::take(&mut row));
+            used = 0;
+        }
+        for &cell in token {
+            let w = cell.0.width();
+            if used > 0 && used + w > width {
+                rows.push(std::mem::take(&mut row));
+                used = 0;
+            }
+            row.push(cell);
+            used += w;
+        }
+    }
+    if !row.is_empty() || rows.is_empty() {
+        rows.push(row);
+    }
+    rows.into_iter()
+        .map(|row| {
+            let mut spans: Vec<Span<'static>> = Vec::new();
+            for (g, style) in reorder(row) {
+                match spans.last_mut() {
+                    Some(last) if last.style == style => last.content.to_mut().push_str(g),
+                    _ => spans.push(Span::styled(g.to_owned(), style)),
+                }
+            }
+            Line::from(spans).style(line.style).right_aligned()
+        })
+        .collect()
+}
+
+/// Cells of a right-to-left line in the order they are shown: runs of
+/// left-to-right text keep their order, everything else is reversed, and
+/// spaces and punctuation between two left-to-right runs join them.
+fn reorder(cells: Vec<Cell>) -> Vec<Cell> {
+    let classes: Vec<Class> = cells.iter().map(|c| class(c.0)).collect();
+    let mut left: Vec<bool> = classes.iter().map(|&c| c == Class::Left).collect();
+    let mut i = 0;
+    while i < classes.len() {
+        if classes[i] != Class::Neutral {
+            i += 1;
+            continue;
+        }
+        let end = (i..classes.len())
+            .find(|&j| classes[j] != Class::Neutral)
+            .unwrap_or(classes.len());
+        let before = i.checked_sub(1).map(|j| classes[j]);
+        let after = classes.get(end).copied();
+        if before == Some(Class::Left) && after == Some(Class::Left) {
+            left[i..end].fill(true);
+        }
+        i = end;
+    }
+
+    let mut shown = Vec::with_capacity(cells.len());
+    let mut end = cells.len();
+    while end > 0 {
+        let start = (0..end)
+            .rev()
+            .find(|&j| left[j] != left[end - 1])
+            .map_or(0, |j| j + 1);
+        if left[start] {
+            shown.extend_from_slice(&cells[start..end]);
+        } else {
+            shown.extend(cells[start..end].iter().rev().map(|&(g, s)| (mirror(g), s)));
+        }
+        end = start;
+    }
+    shown
+}
+
+/// The glyph a bracket is drawn with in right-to-left text.
+fn mirror(g: &str) -> &str {
+    match g {
+        "(" => ")",
+        ")" => "(",
+        "[" => "]",
+        "]" => "[",
+        "{" => "}",
+        "}" => "{",
+        "<" => ">",
+        ">" => "<",
+        "«" => "»",
+        "»" => "«",
+        _ => g,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_reorder() {
+        let shown = |s: &str| {
+            let line = Line::from(s);
+            wrap(&line, 80).iter().map(text).collect::<Vec<_>>()
+        };
+        assert_eq!(shown("אבג דה"), ["הד גבא"]);
+        // Numbers and Latin words read left to right inside the line.
+        assert_eq!(shown("12 אבג"), ["גבא 12"]);
+        assert_eq!(shown("אב (Gen 1) ג"), ["ג (Gen 1) בא"]);
+        // Points stay on their letters.
+        let word = "בְּרֵאשִׁית";
+        assert_eq!(
+            shown(word),
+            [word.graphemes(true).rev().collect::<String>()]
+        );
+        assert_eq!(detect("  3 בְּרֵאשִׁית"), Some(Direction::Rtl));
+        assert_eq!(detect("12 In the beginning"), Some(Direction::Ltr));
+        assert_eq!(detect("12"), None);
+    }
+
+    #[test]
+    fn test_wrap() {
+        let line = Line::from(vec![
+            Span::raw("אבג דה "),
+            Span::styled("וזח", Style::new().bold()),
+            Span::raw(" טי"),
+        ]);
+        let lines = wrap(&line, 7);
+        assert_eq!(
+            lines.iter().map(text).collect::<Vec<_>>(),
+            ["הד גבא", "יט חזו"]
+        );
+        assert_eq!(lines[1].spans[1], Span::styled("חזו", Style::new().bold()));
+        assert_eq!(lines[0].alignment, Some(Alignment::Right));
+        // A word longer than the line is broken inside.
+        assert_eq!(
+            wrap(&Line::from("אבגדה"), 3)
+                .iter()
+                .map(text)
+                .collect::<Vec<_>>(),
+            ["גבא", "הד"]
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! Cursor columns count grapheme clusters, so that a column is what a
+//! reader sees as one character whatever its encoding: an accented letter
+//! written with combining marks, a pointed Hebrew consonant, a CJK ideograph.
+//! These convert them to the byte offsets the text is sliced at and the
+//! display width it takes on screen.
+
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Number of columns in `text`.
+pub fn len(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+/// Byte offset where column `col` starts; the end of the text past its last
+/// column.
+pub fn byte(text: &str, col: usize) -> usize {
+    text.grapheme_indices(true)
+        .nth(col)
+        .map_or(text.len(), |(i, _)| i)
+}
+
+/// Column holding the byte at `offset`.
+pub fn col(text: &str, offset: usize) -> usize {
+    text.grapheme_indices(true)
+        .take_while(|&(i, g)| i + g.len() <= offset)
+        .count()
+}
+
+/// Byte range of columns `start..end`.
+pub fn range(text: &str, cols: Range<usize>) -> Range<usize> {
+    byte(text, cols.start)..byte(text, cols.end.max(cols.start))
+}
+
+/// Screen cells taken by the columns before `col`.
+pub fn width(text: &str, col: usize) -> usize {
+    text[..byte(text, col)].width()
+}
+
+/// Column drawn at screen cell `cells`, or the last one if the text is
+/// narrower.
+pub fn col_at_width(text: &str, cells: usize) -> usize {
+    let mut used = 0;
+    for (i, g) in text.graphemes(true).enumerate() {
+        used += g.width();
+        if used > cells {
+            return i;
+        }
+    }
+    len(text).saturating_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_columns() {
+        // Decomposed é, then a pointed bet and shin.
+        let text = "Dios e\u{301} בְּרֵאשִׁית";
+        assert_eq!(len(text), 13);
+        assert_eq!(&text[range(text, 5..6)], "e\u{301}");
+        assert_eq!(&text[range(text, 7..9)], "בְּרֵ");
+        assert_eq!(col(text, byte(text, 8)), 8);
+        assert_eq!(col(text, byte(text, 8) + 1), 8);
+        assert_eq!(byte(text, 99), text.len());
+    }
+
+    #[test]
+    fn test_width() {
+        let text = "太初有道, Ἐν ἀρχῇ";
+        assert_eq!(width(text, 4), 8);
+        assert_eq!(width(text, 7), 11);
+        assert_eq!(col_at_width(text, 3), 1);
+        assert_eq!(col_at_width(text, 9), 5);
+        assert_eq!(col_at_width(text, 99), len(text) - 1);
+        assert_eq!(col_at_width("", 4), 0);
+    }
+}
@@ -0,0 +1,232 @@
+use std::sync::Arc;
+
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    action::Action,
+    app::Focus,
+    bible::{
+        Bible,
+        search::{Mode, Pattern},
+    },
+    components::{Component, HitList},
+    library::Translation,
+};
+
+struct Hit {
+    book: String,
+    chapter: u16,
+    verse: u16,
+    text: String,
+}
+
+/// Full-text search popup: a query line over the matching verses, with the
+/// number of hits in each book beside them.
+pub struct Search {
+    bible: Arc<Bible>,
+    input: String,
+    mode: Mode,
+    /// Whether keys go to the query line rather than the results.
+    editing: bool,
+    error: Option<String>,
+    hits: Vec<Hit>,
+    /// Each book with hits and how many, in bible order.
+    books: Vec<(String, usize)>,
+    selected: usize,
+    list_state: ListState,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl Search {
+    pub fn new() -> Self {
+        Self {
+            bible: Arc::default(),
+            input: String::new(),
+            mode: Mode::default(),
+            editing: true,
+            error: None,
+            hits: Vec::new(),
+            books: Vec::new(),
+            selected: 0,
+            list_state: ListState::default(),
+            action_tx: None,
+        }
+    }
+
+    fn run(&mut self) {
+        self.hits.clear();
+        self.books.clear();
+        self.selected = 0;
+        let pattern = match Pattern::new(&self.input, self.mode) {
+            Ok(pattern) => pattern,
+            Err(e) => {
+                self.error = Some(e.to_string());
+                return;
+            }
+        };
+        let index = self.bible.search_index();
+        let (mut at, mut chapter) = (None, Vec::new());
+        for id in pattern.search(&self.bible) {
+            let (book, c, verse) = index.verse(id);
+            if at != Some((book, c)) {
+                at = Some((book, c));
+                chapter = self.bible.passage(book, c);
+            }
+            let text = chapter
+                .iter()
+                .find(|v| v.verse == verse)
+                .map_or_else(String::new, |v| v.text.clone());
+            match self.books.last_mut() {
+                Some((b, n)) if b == book => *n += 1,
+                _ => self.books.push((book.to_owned(), 1)),
+            }
+            self.hits.push(Hit {
+                book: book.to_owned(),
+                chapter: c,
+                verse,
+                text,
+            });
+        }
+        self.error = self
+            .hits
+            .is_empty()
+            .then(|| format!("no verses match “{}”", self.input.trim()));
+    }
+
+    /// Move the selection to the first hit of the next (or, going back, the
+    /// current or previous) book.
+    fn skip_book(&mut self, forward: bool) {
+        let Some(hit) = self.hits.get(self.selected) else {
+            return;
+        };
+        let book = hit.book.clone();
+        if forward {
+            if let Some(i) = self.hits[self.selected..]
+                .iter()
+                .position(|h| h.book != book)
+            {
+                self.selected += i;
+            }
+        } else {
+            let start = |i: usize| {
+                let book = &self.hits[i].book;
+                self.hits[..i]
+                    .iter()
+                    .rposition(|h| h.book != *book)
+                    .map_or(0, |j| j + 1)
+            };
+            let first = start(self.selected);
+            self.selected = if first == self.selected && first > 0 {
+                start(first - 1)
+            } else {
+                first
+            };
+        }
+    }
+}
+
+impl Component for Search {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_bible_handler(&mut self, bible: Arc<Bible>, _: &Translation) -> Result<()> {
+        self.bible = bible;
+        if !self.hits.is_empty() {
+            self.run();
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if action == Action::Search {
+            self.editing = true;
+        }
+        Ok(None)
+    }
+
+    fn handle_key_event(&mut self, key: crossterm::event::KeyEvent) -> Result<Option<Action>> {
+        use crossterm::event::{KeyCode::*, KeyModifiers};
+        if self.editing {
+            match key.code {
+                Esc => return Ok(Some(Action::ClosePopup)),
+                Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.mode = self.mode.next();
+                    if !self.hits.is_empty() {
+                        self.run();
+                    }
+                }
+                Enter => {
+                    self.run();
+                    self.editing = self.hits.is_empty();
+                }
+                Down | Tab if !self.hits.is_empty() => self.editing = false,
+                Backspace => {
+                    self.input.pop();
+                }
+                Char(c) => self.input.push(c),
+                _ => {}
+            }
+            return Ok(None);
+        }
+        match key.code {
+            Up | Char('k') => self.selected = self.selected.saturating_sub(1),
+            Down | Char('j') if self.selected + 1 < self.hits.len() => self.selected += 1,
+            Tab => self.skip_book(true),
+            BackTab => self.skip_book(false),
+            Char('/') | Char('i') => self.editing = true,
+            Enter => {
+                if let (Some(hit), Some(tx)) = (self.hits.get(self.selected), &self.action_tx) {
+                    tx.send(Action::MarkMatches {
+                        query: self.input.clone(),
+                        mode: self.mode,
+                    })?;
+                    tx.send(Action::OpenPassage {
+                        book: hit.book.clone(),
+                        chapter: hit.chapter,
+                    })?;
+                    tx.send(Action::SelectVerse {
+                        verse: hit.verse,
+                        end: None,
+                    })?;
+                    return Ok(Some(Action::ClosePopup));
+                }
+            }
+            Esc | Char('q') => return Ok(Some(Action::ClosePopup)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame, area: Rect, focus: Focus) -> Result<()> {
+        if focus != Focus::Search {
+            return Ok(());
+        }
+        let hits = self
+            .hits
+            .iter()
+            .map(|h| {
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{} {}:{}  ", h.book, h.chapter, h.verse)),
+                    Span::styled(h.text.as_str(), Style::default().dim()),
+                ]))
+            })
+            .collect();
+        self.list_state.select(Some(self.selected));
+        HitList {
+            title: format!("Search · {} (Ctrl-t)", self.mode),
+            input: &self.input,
+            editing: self.editing,
+            message: self.error.as_deref().map(|e| Span::raw(e).red()),
+            books: &self.books,
+            current: self.hits.get(self.selected).map(|h| h.book.as_str()),
+            hits_title: format!("{} verses", self.hits.len()),
+            hits,
+        }
+        .render(f, area, &mut self.list_state);
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+use std::sync::Arc;
+
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    action::Action,
+    app::Focus,
+    bible::{Bible, reference::VerseRange},
+    components::{Component, list_popup},
+    library::Translation,
+    tags::{Tag, Tags},
+};
+
+/// Every tag with how many verses it is on; Enter opens a tag to its
+/// verses, with their text in the open translation, as a topical index.
+pub struct TagBrowser {
+    bible: Arc<Bible>,
+    translation: String,
+    tags: Tags,
+    names: Vec<(String, usize)>,
+    /// The tag opened, with its verses and their text.
+    opened: Option<(String, Vec<(Tag, String)>)>,
+    /// Selected tag, and selected verse of the opened one.
+    selected: usize,
+    verse: usize,
+    list_state: ListState,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl TagBrowser {
+    pub fn new() -> Self {
+        Self {
+            bible: Arc::default(),
+            translation: String::new(),
+            tags: Tags::default(),
+            names: Vec::new(),
+            opened: None,
+            selected: 0,
+            verse: 0,
+            list_state: ListState::default(),
+            action_tx: None,
+        }
+    }
+
+    fn refresh(&mut self) {
+        self.names = self.tags.names();
+        self.selected = self.selected.min(self.names.len().saturating_sub(1));
+        if let Some((name, _)) = self.opened.take() {
+            self.open(name);
+        }
+    }
+
+    /// Show the verses tagged `name`, or go back to the tags once it has
+    /// none left.
+    fn open(&mut self, name: String) {
+        let verses: Vec<(Tag, String)> = self
+            .tags
+            .tagged(&name)
+            .into_iter()
+            .map(|t| (t.clone(), self.text(t)))
+            .collect();
+        if verses.is_empty() {
+            return;
+        }
+        self.verse = self.verse.min(verses.len() - 1);
+        self.opened = Some((name, verses));
+    }
+
+    /// The text of the verses a tag is on, joined by spaces.
+    fn text(&self, tag: &Tag) -> String {
+        self.bible
+            .passage(&tag.book, tag.chapter)
+            .iter()
+            .filter(|v| (tag.verse..=tag.end).contains(&v.verse))
+            .map(|v| v.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl Component for TagBrowser {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_bible_handler(
+        &mut self,
+        bible: Arc<Bible>,
+        translation: &Translation,
+    ) -> Result<()> {
+        self.bible = bible;
+        self.translation = translation.abbrev.clone();
+        self.refresh();
+        Ok(())
+    }
+
+    fn register_tags_handler(&mut self, tags: &Tags) -> Result<()> {
+        self.tags = tags.clone();
+        self.refresh();
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if action == Action::Tags {
+            self.opened = None;
+        }
+        Ok(None)
+    }
+
+    fn handle_key_event(&mut self, key: crossterm::event::KeyEvent) -> Result<Option<Action>> {
+        use crossterm::event::KeyCode::*;
+        let Some((_, verses)) = &self.opened else {
+            match key.code {
+                Up | Char('k') => self.selected = self.selected.saturating_sub(1),
+                Down | Char('j') if self.selected + 1 < self.names.len() => self.selected += 1,
+                Enter | Right | Char('l') => {
+                    if let Some((name, _)) = self.names.get(self.selected) {
+                        self.verse = 0;
+                        self.open(name.clone());
+                    }
+                }
+                Esc | Char('q') => return Ok(Some(Action::ClosePopup)),
+                _ => {}
+            }
+            return Ok(None);
+        };
+        let selected = verses.get(self.verse).map(|(t, _)| t);
+        match key.code {
+            Up | Char('k') => self.verse = self.verse.saturating_sub(1),
+            Down | Char('j') if self.verse + 1 < verses.len() => self.verse += 1,
+            Char('d') | Delete => {
+                if let Some(tag) = selected {
+                    return Ok(Some(Action::DeleteTag(tag.clone())));
+                }
+            }
+            Enter => {
+                if let (Some(tag), Some(tx)) = (selected, &self.action_tx) {
+                    tx.send(Action::OpenPassage {
+                        book: tag.book.clone(),
+                        chapter: tag.chapter,
+                    })?;
+                    tx.send(Action::SelectVerse {
+                        verse: tag.verse,
+                        end: (tag.end != tag.verse).then_some(tag.end),
+                    })?;
+                    return Ok(Some(Action::ClosePopup));
+                }
+            }
+            Esc | Left | Backspace | Char('h') => self.opened = None,
+            Char('q') => return Ok(Some(Action::ClosePopup)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame, area: Rect, focus: Focus) -> Result<()> {
+        if focus != Focus::Tags {
+            return Ok(());
+        }
+        let (title, hint, items, selected) = match &self.opened {
+            None => {
+                let items: Vec<ListItem> = self
+                    .names
+                    .iter()
+                    .map(|(name, count)| {
+                        let verses = if *count == 1 { "verse" } else { "verses" };
+                        ListItem::new(Line::from(vec![
+                            Span::raw(format!(" #{name:<30}")),
+                            Span::styled(format!("{count} {verses}"), Style::default().dim()),
+                        ]))
+                    })
+                    .collect();
+                let title = format!("Tags · {}", self.names.len());
+                (
+                    title,
+                    "Enter open · # in the reader tags",
+                    items,
+                    self.selected,
+                )
+            }
+            Some((name, verses)) => {
+                let items: Vec<ListItem> = verses
+                    .iter()
+                    .map(|(tag, text)| {
+                        ListItem::new(Line::from(vec![
+                            Span::raw(format!(" {:<24}", tag.reference())),
+                            Span::styled(text.as_str(), Style::default().dim()),
+                        ]))
+                    })
+                    .collect();
+                let title = format!("#{name} · {}", self.translation);
+                (title, "Enter jump · d remove · Esc back", items, self.verse)
+            }
+        };
+        list_popup(
+            f,
+            area,
+            title,
+            Line::from(hint).dim(),
+            items,
+            selected,
+            &mut self.list_state,
+        );
+        Ok(())
+    }
+}
@@ -11,7 +11,7 @@ use ratatui::style::{Color, Modifier, Style};
 use serde::{Deserialize, de::Deserializer};
 use tracing::error;
 
-use crate::{action::Action, app::Mode};
+use crate::{action::Action, app::Mode, library::Translation};
 
 const CONFIG: &str = include_str!("../.config/config.json5");
 
@@ -21,6 +21,19 @@ pub struct AppConfig {
     pub data_dir: PathBuf,
     #[serde(default)]
     pub config_dir: PathBuf,
+    /// SWORD library to read modules from, instead of `$SWORD_PATH` or `~/.sword`.
+    #[serde(default)]
+    pub sword_dir: Option<PathBuf>,
+    /// Translations to offer besides the files in `<data dir>/translations`.
+    #[serde(default)]
+    pub translations: Vec<Translation>,
+    /// Abbreviation of the translation opened when `--translation` is not given.
+    #[serde(default)]
+    pub default_translation: Option<String>,
+    /// Screen lines the reader keeps visible above and below the cursor;
+    /// 3 when unset.
+    #[serde(default)]
+    pub scrolloff: Option<usize>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
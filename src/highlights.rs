@@ -0,0 +1,155 @@
+use std::{ops::Range, path::PathBuf};
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{bible::canon, store};
+
+/// Prefix of the styles in the config that highlights can be drawn in; the
+/// rest of the name is the colour, as in `highlight_yellow`.
+pub const STYLE_PREFIX: &str = "highlight_";
+
+/// A coloured stretch of one translation's text, from a column of one verse
+/// to a column of the same or a later verse of the chapter. Columns count
+/// graphemes, as the reader's cursor does, and the end is exclusive.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Highlight {
+    pub translation: String,
+    pub book: String,
+    pub chapter: u16,
+    /// First verse and the column in it the highlight starts at.
+    pub start: (u16, usize),
+    /// Last verse and the column in it the highlight stops before.
+    pub end: (u16, usize),
+    /// Name of the style it is drawn in, without [`STYLE_PREFIX`].
+    pub colour: String,
+    /// When it was made, in seconds since the Unix epoch.
+    #[serde(default)]
+    pub created: u64,
+}
+
+impl Highlight {
+    pub fn is_in(&self, translation: &str, book: &str, chapter: u16) -> bool {
+        self.translation == translation && self.book == book && self.chapter == chapter
+    }
+
+    /// Columns of `verse` it covers, given how many the verse has.
+    pub fn columns(&self, verse: u16, len: usize) -> Option<Range<usize>> {
+        if verse < self.start.0 || verse > self.end.0 {
+            return None;
+        }
+        let start = if verse == self.start.0 {
+            self.start.1
+        } else {
+            0
+        };
+        let end = if verse == self.end.0 { self.end.1 } else { len };
+        (start < end).then(|| start.min(len)..end.min(len))
+    }
+
+    /// Position in bible order, for listing.
+    pub fn position(&self) -> (usize, &str, u16, (u16, usize)) {
+        (
+            canon::order(&self.book),
+            &self.book,
+            self.chapter,
+            self.start,
+        )
+    }
+
+    /// Whether it shares any text with `other`, of the same chapter.
+    pub fn overlaps(&self, other: &Highlight) -> bool {
+        self.is_in(&other.translation, &other.book, other.chapter)
+            && self.start < other.end
+            && other.start < self.end
+    }
+}
+
+/// Every highlight of every translation; later ones are drawn over earlier
+/// ones.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Highlights {
+    pub list: Vec<Highlight>,
+}
+
+impl Highlights {
+    pub fn load() -> Result<Self> {
+        store::load(&path())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        store::save(&path(), self)
+    }
+
+    /// Add a highlight, dropping those it covers entirely.
+    pub fn add(&mut self, highlight: Highlight) {
+        self.list.retain(|h| {
+            !(h.is_in(&highlight.translation, &highlight.book, highlight.chapter)
+                && highlight.start <= h.start
+                && h.end <= highlight.end)
+        });
+        self.list.push(highlight);
+    }
+
+    pub fn remove(&mut self, highlight: &Highlight) {
+        self.list.retain(|h| h != highlight);
+    }
+
+    pub fn in_chapter<'a>(
+        &'a self,
+        translation: &'a str,
+        book: &'a str,
+        chapter: u16,
+    ) -> impl Iterator<Item = &'a Highlight> {
+        self.list
+            .iter()
+            .filter(move |h| h.is_in(translation, book, chapter))
+    }
+}
+
+fn path() -> PathBuf {
+    store::path("highlights.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn highlight(start: (u16, usize), end: (u16, usize), colour: &str) -> Highlight {
+        Highlight {
+            translation: "KJV".into(),
+            book: "John".into(),
+            chapter: 3,
+            start,
+            end,
+            colour: colour.into(),
+            created: 0,
+        }
+    }
+
+    #[test]
+    fn test_highlights() {
+        let h = highlight((16, 4), (17, 10), "yellow");
+        assert_eq!(h.columns(15, 50), None);
+        assert_eq!(h.columns(16, 50), Some(4..50));
+        assert_eq!(h.columns(17, 50), Some(0..10));
+        assert_eq!(h.columns(17, 6), Some(0..6));
+        assert!(h.overlaps(&highlight((17, 9), (18, 0), "red")));
+        assert!(!h.overlaps(&highlight((17, 10), (18, 0), "red")));
+
+        let mut highlights = Highlights::default();
+        highlights.add(highlight((16, 5), (16, 9), "red"));
+        highlights.add(highlight((17, 0), (18, 3), "green"));
+        highlights.add(h.clone());
+        let colours: Vec<_> = highlights
+            .in_chapter("KJV", "John", 3)
+            .map(|h| h.colour.as_str())
+            .collect();
+        assert_eq!(colours, ["green", "yellow"]);
+        assert_eq!(highlights.in_chapter("WEB", "John", 3).count(), 0);
+        highlights.remove(&h);
+        assert_eq!(highlights.list.len(), 1);
+    }
+}
@@ -0,0 +1,243 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{Result, eyre::eyre};
+use serde::Deserialize;
+
+use crate::{
+    bible::sword,
+    config::{Config, get_config_dir, get_data_dir},
+};
+
+/// A Bible translation the app knows how to open.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct Translation {
+    pub name: String,
+    /// Short name used on the command line and in titles, e.g. `KJV`.
+    pub abbrev: String,
+    #[serde(default)]
+    pub language: String,
+    #[serde(default)]
+    pub license: String,
+    /// Which way the script runs; implied by `language` for Hebrew, Arabic
+    /// and the like when unset.
+    #[serde(default)]
+    pub direction: Option<Direction>,
+    /// A file [`Bible::load`](crate::bible::Bible::load) understands. Relative
+    /// paths are resolved against the config directory.
+    pub path: PathBuf,
+}
+
+/// Writing direction of a translation's script.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
+/// Languages written right to left, by ISO 639 code.
+const RTL_LANGUAGES: &[&str] = &[
+    "ar", "arc", "ckb", "dv", "fa", "he", "hbo", "ps", "syc", "syr", "ur", "yi",
+];
+
+impl Translation {
+    /// Which way the text runs, as configured or implied by the language;
+    /// `None` when neither says, and the text itself has to tell.
+    pub fn direction(&self) -> Option<Direction> {
+        self.direction.or_else(|| {
+            let lang = self.language.split(['-', '_']).next()?.to_ascii_lowercase();
+            RTL_LANGUAGES
+                .contains(&lang.as_str())
+                .then_some(Direction::Rtl)
+        })
+    }
+}
+
+/// Every installed translation: the `translations` listed in the config,
+/// files dropped into `<data dir>/translations`, and the Bible modules of the
+/// SWORD library. The first entry with a given abbreviation wins.
+#[derive(Clone, Debug, Default)]
+pub struct Library {
+    pub translations: Vec<Translation>,
+    default: Option<String>,
+}
+
+impl Library {
+    pub fn new(config: &Config) -> Self {
+        let mut library = Self {
+            translations: Vec::new(),
+            default: config.config.default_translation.clone(),
+        };
+        for t in &config.config.translations {
+            library.add(Translation {
+                path: get_config_dir().join(&t.path),
+                ..t.clone()
+            });
+        }
+        for t in scan_dir(&translations_dir()) {
+            library.add(t);
+        }
+        let sword_dir = config.config.sword_dir.clone().or_else(sword::default_dir);
+        if let Some(modules) = sword_dir.and_then(|dir| sword::modules(&dir).ok()) {
+            for m in modules {
+                library.add(Translation {
+                    name: m.description,
+                    license: m
+                        .conf
+                        .get("DistributionLicense")
+                        .cloned()
+                        .unwrap_or_default(),
+                    direction: match m.conf.get("Direction").map(String::as_str) {
+                        Some("RtoL") => Some(Direction::Rtl),
+                        Some("LtoR") => Some(Direction::Ltr),
+                        _ => None,
+                    },
+                    abbrev: m.name,
+                    language: m.lang,
+                    path: m.path,
+                });
+            }
+        }
+        library
+    }
+
+    fn add(&mut self, translation: Translation) {
+        if self.get(&translation.abbrev).is_none() {
+            self.translations.push(translation);
+        }
+    }
+
+    /// Look a translation up by abbreviation, ignoring case.
+    pub fn get(&self, abbrev: &str) -> Option<&Translation> {
+        self.translations
+            .iter()
+            .find(|t| t.abbrev.eq_ignore_ascii_case(abbrev))
+    }
+
+    /// The translation to open: the one asked for, else the configured
+    /// default, else the first installed one.
+    pub fn resolve(&self, requested: Option<&str>) -> Result<&Translation> {
+        if self.translations.is_empty() {
+            return Err(eyre!(
+                "no Bible translations are installed.\n\n\
+                 Copy a Bible file (TSV, OSIS, USFM, USX, Zefania, OpenSong or a MySword/MyBible \
+                 SQLite database) into\n    {}\n\
+                 or list one under `translations` in {}, e.g.\n    \
+                 \"translations\": [{{ \"name\": \"King James Version\", \"abbrev\": \"KJV\", \
+                 \"path\": \"/path/to/kjv.tsv\" }}]\n\
+                 SWORD modules installed in ~/.sword (or `sword_dir`) are picked up too.",
+                translations_dir().display(),
+                get_config_dir().join("config.json5").display(),
+            ));
+        }
+        let Some(abbrev) = requested.or(self.default.as_deref()) else {
+            return Ok(&self.translations[0]);
+        };
+        self.get(abbrev).ok_or_else(|| {
+            let known: Vec<_> = self
+                .translations
+                .iter()
+                .map(|t| t.abbrev.as_str())
+                .collect();
+            eyre!(
+                "unknown translation “{abbrev}”; installed: {}",
+                known.join(", ")
+            )
+        })
+    }
+}
+
+/// Where translation files can be dropped without editing the config.
+pub fn translations_dir() -> PathBuf {
+    get_data_dir().join("translations")
+}
+
+/// Translations for the files of `dir`, and its USFM project directories,
+/// named after their file stem.
+fn scan_dir(dir: &Path) -> Vec<Translation> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() || !crate::bible::usfm_files(p).is_empty())
+        .filter(|p| {
+            p.file_name()
+                .is_some_and(|n| !n.to_string_lossy().starts_with('.'))
+        })
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let stem = path.file_stem()?.to_string_lossy().into_owned();
+            Some(Translation {
+                name: stem.clone(),
+                abbrev: stem.to_uppercase(),
+                path,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn library(default: Option<&str>) -> Library {
+        let t = |abbrev: &str| Translation {
+            name: abbrev.into(),
+            abbrev: abbrev.into(),
+            ..Default::default()
+        };
+        Library {
+            translations: vec![t("KJV"), t("WEB")],
+            default: default.map(Into::into),
+        }
+    }
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(library(None).resolve(None).unwrap().abbrev, "KJV");
+        assert_eq!(library(Some("WEB")).resolve(None).unwrap().abbrev, "WEB");
+        assert_eq!(
+            library(Some("WEB")).resolve(Some("kjv")).unwrap().abbrev,
+            "KJV"
+        );
+        assert_eq!(
+            library(None).resolve(Some("ESV")).unwrap_err().to_string(),
+            "unknown translation “ESV”; installed: KJV, WEB"
+        );
+        assert!(
+            Library::default()
+                .resolve(None)
+                .unwrap_err()
+                .to_string()
+                .starts_with("no Bible translations are installed")
+        );
+    }
+
+    #[test]
+    fn test_direction() {
+        let t = |language: &str, direction| Translation {
+            language: language.into(),
+            direction,
+            ..Default::default()
+        };
+        assert_eq!(t("he", None).direction(), Some(Direction::Rtl));
+        assert_eq!(t("ar-EG", None).direction(), Some(Direction::Rtl));
+        assert_eq!(t("en", None).direction(), None);
+        assert_eq!(t("", None).direction(), None);
+        assert_eq!(
+            t("he", Some(Direction::Ltr)).direction(),
+            Some(Direction::Ltr)
+        );
+    }
+}
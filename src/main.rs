@@ -7,11 +7,18 @@ use crate::app::App;
 mod action;
 mod app;
 mod bible;
+mod bookmarks;
 mod cli;
 mod components;
 mod config;
 mod errors;
+mod highlights;
+mod library;
 mod logging;
+mod notes;
+mod plans;
+mod store;
+mod tags;
 mod tui;
 
 #[tokio::main]
@@ -20,7 +27,54 @@ async fn main() -> Result<()> {
     crate::logging::init()?;
 
     let args = Cli::parse();
-    let mut app = App::new(args.tick_rate, args.frame_rate)?;
+    if args.list_modules {
+        return list_modules();
+    }
+    if let Some(path) = &args.import_plan {
+        let mut plans = plans::Plans::load()?;
+        let plan = plans.import(path)?;
+        println!("imported “{}”, {} days", plan.name, plan.days.len());
+        return Ok(());
+    }
+    if let Some(reference) = &args.print {
+        return print_passages(reference, args.translation.as_deref());
+    }
+    let mut app = App::new(args.tick_rate, args.frame_rate, args.translation)?;
     app.run().await?;
     Ok(())
 }
+
+fn print_passages(reference: &str, translation: Option<&str>) -> Result<()> {
+    let passages = bible::reference::parse(reference)?;
+    let config = config::Config::new()?;
+    let library = library::Library::new(&config);
+    let translation = library.resolve(translation)?;
+    let bible = bible::Bible::load(&translation.path)?;
+    for passage in passages {
+        println!("{passage} ({})", translation.abbrev);
+        let verses = passage.resolve(&bible)?;
+        let mut chapter = passage.start.chapter;
+        for v in verses {
+            if v.chapter != chapter {
+                chapter = v.chapter;
+                println!("{}", v.chapter);
+            }
+            println!("{:>3} {}", v.verse, v.text);
+        }
+    }
+    Ok(())
+}
+
+fn list_modules() -> Result<()> {
+    let config = config::Config::new()?;
+    let Some(dir) = config.config.sword_dir.or_else(bible::sword::default_dir) else {
+        return Err(color_eyre::eyre::eyre!("no SWORD library directory found"));
+    };
+    for module in bible::sword::modules(&dir)? {
+        println!(
+            "{:<12} {:<4} {}",
+            module.name, module.lang, module.description
+        );
+    }
+    Ok(())
+}
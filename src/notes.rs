@@ -0,0 +1,322 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{bible::reference::VerseRange, store};
+
+/// Notes on a verse or a range of verses of one chapter, in Markdown.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Note {
+    pub book: String,
+    pub chapter: u16,
+    pub verse: u16,
+    /// Last verse the note is on; `verse` itself for a single verse.
+    pub end: u16,
+    pub body: String,
+    /// When it was written and last changed, in seconds since the Unix epoch.
+    pub created: u64,
+    pub updated: u64,
+}
+
+impl VerseRange for Note {
+    fn range(&self) -> (&str, u16, u16, u16) {
+        (&self.book, self.chapter, self.verse, self.end)
+    }
+}
+
+impl Note {
+    pub fn new(book: String, chapter: u16, verse: u16, end: u16) -> Self {
+        Self {
+            book,
+            chapter,
+            verse,
+            end: end.max(verse),
+            created: store::now(),
+            ..Default::default()
+        }
+    }
+
+    /// Whether `other` is on the same verses.
+    fn is_on(&self, other: &Note) -> bool {
+        self.range() == other.range()
+    }
+
+    /// File the note is kept in, named after its reference.
+    fn file_name(&self) -> String {
+        let mut name = format!("{}-{}-{}", self.book, self.chapter, self.verse);
+        if self.end != self.verse {
+            name.push_str(&format!("-{}", self.end));
+        }
+        format!("{}.md", name.replace(' ', "_"))
+    }
+
+    /// The note as a Markdown file, its reference and dates in front matter.
+    /// The reference is for people reading the file; the verses are read
+    /// back from keys of their own.
+    pub fn to_markdown(&self) -> String {
+        format!(
+            "---\nreference: {}\nbook: {}\nchapter: {}\nverse: {}\nend: {}\n\
+             created: {}\nupdated: {}\n---\n\n{}\n",
+            self.reference(),
+            self.book,
+            self.chapter,
+            self.verse,
+            self.end,
+            self.created,
+            self.updated,
+            self.body.trim_end()
+        )
+    }
+
+    pub fn from_markdown(text: &str) -> Result<Self> {
+        let text = text.replace("\r\n", "\n");
+        let rest = text
+            .strip_prefix("---\n")
+            .ok_or_else(|| eyre!("no front matter"))?;
+        let (front, body) = rest
+            .split_once("\n---\n")
+            .ok_or_else(|| eyre!("front matter is not closed"))?;
+        let mut note = Note::default();
+        let number = |key: &str, value: &str| {
+            value
+                .parse::<u16>()
+                .wrap_err_with(|| format!("{key} “{value}” is not a number"))
+        };
+        for line in front.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "book" => note.book = value.to_owned(),
+                "chapter" => note.chapter = number(key, value)?,
+                "verse" => note.verse = number(key, value)?,
+                "end" => note.end = number(key, value)?,
+                "created" => note.created = value.parse().unwrap_or_default(),
+                "updated" => note.updated = value.parse().unwrap_or_default(),
+                _ => {}
+            }
+        }
+        if note.book.is_empty() {
+            return Err(eyre!("front matter names no book"));
+        }
+        if note.chapter == 0 || note.verse == 0 {
+            return Err(eyre!("front matter names no verse"));
+        }
+        note.end = note.end.max(note.verse);
+        note.body = body.trim().to_owned();
+        Ok(note)
+    }
+}
+
+/// Let `$VISUAL` or `$EDITOR` change the text of a note, through a
+/// temporary file; the terminal has to be handed over while it runs.
+pub fn edit_in_editor(note: &Note) -> Result<Note> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_owned());
+    let mut words = editor.split_whitespace();
+    let program = words.next().ok_or_else(|| eyre!("$EDITOR is empty"))?;
+    let path = std::env::temp_dir().join(format!("logos-note-{}.md", std::process::id()));
+    fs::write(&path, &note.body).wrap_err_with(|| format!("cannot write {path:?}"))?;
+    let status = Command::new(program)
+        .args(words)
+        .arg(&path)
+        .status()
+        .wrap_err_with(|| format!("cannot run {editor}"));
+    let body = fs::read_to_string(&path).wrap_err_with(|| format!("cannot read {path:?}"));
+    let _ = fs::remove_file(&path);
+    let status = status?;
+    if !status.success() {
+        return Err(eyre!("{editor} failed: {status}"));
+    }
+    Ok(Note {
+        body: body?,
+        ..note.clone()
+    })
+}
+
+/// Every note, each kept as a Markdown file in the `notes` data directory.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Notes {
+    pub list: Vec<Note>,
+    dir: PathBuf,
+}
+
+impl Notes {
+    pub fn load() -> Result<Self> {
+        Self::load_from(&store::path("notes"))
+    }
+
+    /// Read every note in `dir`; files that are not notes are skipped.
+    fn load_from(dir: &Path) -> Result<Self> {
+        let mut notes = Self {
+            list: Vec::new(),
+            dir: dir.to_path_buf(),
+        };
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Ok(notes);
+        };
+        for path in entries.filter_map(|e| e.ok().map(|e| e.path())) {
+            if path.extension().is_none_or(|e| e != "md") {
+                continue;
+            }
+            let text = match fs::read_to_string(&path) {
+                Ok(text) => text,
+                Err(e) => {
+                    warn!("skipping {path:?}: {e}");
+                    continue;
+                }
+            };
+            match Note::from_markdown(&text) {
+                Ok(note) => notes.list.push(note),
+                Err(e) => warn!("skipping {path:?}: {e:#}"),
+            }
+        }
+        notes.list.sort_by(|a, b| a.position().cmp(&b.position()));
+        Ok(notes)
+    }
+
+    /// Write a note out, replacing the one on the same verses; a note left
+    /// empty is deleted.
+    pub fn save(&mut self, mut note: Note) -> Result<()> {
+        if note.body.trim().is_empty() {
+            return self.delete(&note);
+        }
+        note.updated = store::now();
+        fs::create_dir_all(&self.dir).wrap_err_with(|| format!("cannot create {:?}", self.dir))?;
+        let path = self.dir.join(note.file_name());
+        fs::write(&path, note.to_markdown()).wrap_err_with(|| format!("cannot write {path:?}"))?;
+        self.list.retain(|n| !n.is_on(&note));
+        let at = self
+            .list
+            .partition_point(|n| n.position() < note.position());
+        self.list.insert(at, note);
+        Ok(())
+    }
+
+    pub fn delete(&mut self, note: &Note) -> Result<()> {
+        let path = self.dir.join(note.file_name());
+        if path.exists() {
+            fs::remove_file(&path).wrap_err_with(|| format!("cannot remove {path:?}"))?;
+        }
+        self.list.retain(|n| !n.is_on(note));
+        Ok(())
+    }
+
+    /// Notes on `verse`.
+    pub fn on(&self, book: &str, chapter: u16, verse: u16) -> impl Iterator<Item = &Note> {
+        let book = book.to_owned();
+        self.list
+            .iter()
+            .filter(move |n| n.covers(&book, chapter, verse))
+    }
+
+    /// The note on exactly these verses.
+    pub fn get(&self, book: &str, chapter: u16, verse: u16, end: u16) -> Option<&Note> {
+        self.list
+            .iter()
+            .find(|n| n.book == book && n.chapter == chapter && n.verse == verse && n.end == end)
+    }
+
+    /// Notes whose reference or text has every word of `query`, ignoring
+    /// case; every note when it is blank.
+    pub fn search(&self, query: &str) -> Vec<&Note> {
+        let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        self.list
+            .iter()
+            .filter(|n| {
+                let text = format!("{}\n{}", n.reference(), n.body).to_lowercase();
+                terms.iter().all(|t| text.contains(t))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_markdown() {
+        let note = Note {
+            book: "1 John".into(),
+            chapter: 4,
+            verse: 7,
+            end: 8,
+            body: "# Love\n\nGod *is* love.".into(),
+            created: 10,
+            updated: 20,
+        };
+        let text = note.to_markdown();
+        assert_eq!(
+            text,
+            "---\nreference: 1 John 4:7-8\nbook: 1 John\nchapter: 4\nverse: 7\nend: 8\n\
+             created: 10\nupdated: 20\n---\n\n# Love\n\nGod *is* love.\n"
+        );
+        assert_eq!(Note::from_markdown(&text).unwrap(), note);
+        assert_eq!(
+            Note::from_markdown(&text.replace('\n', "\r\n")).unwrap(),
+            note
+        );
+        assert_eq!(note.file_name(), "1_John-4-7-8.md");
+        assert!(Note::from_markdown("# Love").is_err());
+        assert!(Note::from_markdown("---\nreference: John 3\n---\n").is_err());
+        assert!(Note::from_markdown("---\nbook: John\nchapter: 3\n---\n").is_err());
+
+        // A book outside the canon.
+        let tobit = Note {
+            book: "Tobit".into(),
+            chapter: 4,
+            verse: 15,
+            end: 15,
+            ..Default::default()
+        };
+        assert_eq!(Note::from_markdown(&tobit.to_markdown()).unwrap(), tobit);
+    }
+
+    #[test]
+    fn test_notes() {
+        let dir = std::env::temp_dir().join(format!("logos-notes-{}", std::process::id()));
+        let mut notes = Notes::load_from(&dir).unwrap();
+        let note = |book: &str, chapter, verse, end, body: &str| Note {
+            body: body.into(),
+            ..Note::new(book.into(), chapter, verse, end)
+        };
+        notes
+            .save(note("John", 3, 16, 18, "For God so loved"))
+            .unwrap();
+        notes
+            .save(note("Genesis", 1, 1, 1, "In the beginning"))
+            .unwrap();
+        notes
+            .save(note("John", 3, 16, 18, "Loved the world"))
+            .unwrap();
+        assert_eq!(
+            notes.list.iter().map(Note::reference).collect::<Vec<_>>(),
+            ["Genesis 1:1", "John 3:16-18"]
+        );
+        assert_eq!(Notes::load_from(&dir).unwrap().list, notes.list);
+        assert_eq!(notes.on("John", 3, 17).count(), 1);
+        assert_eq!(notes.on("John", 3, 19).count(), 0);
+        assert_eq!(notes.search("WORLD john").len(), 1);
+        assert_eq!(notes.search("").len(), 2);
+
+        notes.save(note("Genesis", 1, 1, 1, "  ")).unwrap();
+        // A file that is not UTF-8 is skipped rather than failing the rest.
+        fs::write(dir.join("broken.md"), b"\xff\xfe").unwrap();
+        assert_eq!(Notes::load_from(&dir).unwrap().list.len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,480 @@
+//! Reading plans: the readings of each day, and how far through each plan
+//! the reader is.
+
+use std::{
+    collections::BTreeSet,
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{bible::reference, store};
+
+mod builtin;
+
+/// A plan: for each day, the references to read, such as `Genesis 1-3`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Plan {
+    pub name: String,
+    pub days: Vec<Vec<String>>,
+}
+
+/// A plan as a JSON file may give it: with or without a name, and each day
+/// a list of references or one string of them separated by semicolons.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PlanFile {
+    Named {
+        name: Option<String>,
+        days: Vec<Day>,
+    },
+    Days(Vec<Day>),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Day {
+    Readings(Vec<String>),
+    Line(String),
+}
+
+impl Day {
+    fn readings(self) -> Vec<String> {
+        match self {
+            Day::Readings(readings) => readings,
+            Day::Line(line) => split(&line, ';'),
+        }
+    }
+}
+
+impl Plan {
+    /// Read a plan from a `.json` or `.csv` file, named after the file unless
+    /// it names itself.
+    pub fn read(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path).wrap_err_with(|| format!("cannot read {path:?}"))?;
+        let name = path
+            .file_stem()
+            .map_or_else(String::new, |s| s.to_string_lossy().replace('_', " "));
+        let plan = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json(&name, &text),
+            Some("csv") => Self::from_csv(&name, &text),
+            _ => Err(eyre!("not a .json or .csv file")),
+        }
+        .wrap_err_with(|| format!("cannot read a plan from {path:?}"))?;
+        plan.check()
+            .wrap_err_with(|| format!("cannot read a plan from {path:?}"))?;
+        Ok(plan)
+    }
+
+    fn from_json(name: &str, text: &str) -> Result<Self> {
+        let (named, days) = match serde_json::from_str(text)? {
+            PlanFile::Named { name, days } => (name, days),
+            PlanFile::Days(days) => (None, days),
+        };
+        Ok(Self {
+            name: named.unwrap_or_else(|| name.to_owned()),
+            days: days.into_iter().map(Day::readings).collect(),
+        })
+    }
+
+    /// One row per day, its cells the references to read. A leading day
+    /// number, a `day,…` header, blank lines and `#` comments are skipped,
+    /// and a cell in double quotes may hold commas.
+    fn from_csv(name: &str, text: &str) -> Result<Self> {
+        let mut days = Vec::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut cells = split(line, ',');
+            if days.is_empty() && cells.first().is_some_and(|c| c.eq_ignore_ascii_case("day")) {
+                continue;
+            }
+            if cells.first().is_some_and(|c| c.parse::<usize>().is_ok()) {
+                cells.remove(0);
+            }
+            days.push(cells);
+        }
+        Ok(Self {
+            name: name.to_owned(),
+            days,
+        })
+    }
+
+    /// Make sure the plan has days, and that every reading is a reference.
+    pub fn check(&self) -> Result<()> {
+        if self.days.is_empty() {
+            return Err(eyre!("plan “{}” has no days", self.name));
+        }
+        for (i, day) in self.days.iter().enumerate() {
+            for reading in day {
+                reference::parse(reading).map_err(|e| eyre!("day {}: “{reading}”: {e}", i + 1))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Cells of a line split at `separator`, trimmed, outside double quotes.
+fn split(line: &str, separator: char) -> Vec<String> {
+    let mut cells = vec![String::new()];
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => cells.push(String::new()),
+            c => cells.last_mut().unwrap().push(c),
+        }
+    }
+    cells
+        .into_iter()
+        .map(|c| c.trim().to_owned())
+        .filter(|c| !c.is_empty())
+        .collect()
+}
+
+/// A calendar day, counted from 1970-01-01; written as `2026-03-01`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Date(i64);
+
+impl Date {
+    /// The local date.
+    pub fn today() -> Self {
+        let now = store::now() as i64;
+        Self::local(now).unwrap_or(Self(now.div_euclid(86_400)))
+    }
+
+    #[cfg(unix)]
+    fn local(now: i64) -> Option<Self> {
+        let time = now as libc::time_t;
+        // SAFETY: `tm` is plain data that localtime_r fills in, or leaves
+        // alone when it returns null.
+        let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+        let tm = unsafe { libc::localtime_r(&time, &mut tm).as_ref()? };
+        Self::from_ymd(
+            i64::from(tm.tm_year) + 1900,
+            tm.tm_mon as u32 + 1,
+            tm.tm_mday as u32,
+        )
+    }
+
+    #[cfg(not(unix))]
+    fn local(_: i64) -> Option<Self> {
+        None
+    }
+
+    /// The date of a year, month and day, if there is one.
+    pub fn from_ymd(year: i64, month: u32, day: u32) -> Option<Self> {
+        let days_in_month = match month {
+            2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            1..=12 => 31,
+            _ => return None,
+        };
+        if day == 0 || day > days_in_month {
+            return None;
+        }
+        // Days from civil, counting years from March so leap days come last.
+        let year = if month <= 2 { year - 1 } else { year };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = i64::from((month + 9) % 12);
+        let day_of_year = (153 * month + 2) / 5 + i64::from(day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        Some(Self(era * 146_097 + day_of_era - 719_468))
+    }
+
+    fn ymd(self) -> (i64, u32, u32) {
+        let days = self.0 + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * month + 2) / 5 + 1) as u32;
+        let month = if month < 10 { month + 3 } else { month - 9 } as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        (year, month, day)
+    }
+
+    /// The date `days` later.
+    pub fn plus(self, days: i64) -> Self {
+        Self(self.0 + days)
+    }
+
+    /// Days from `earlier` to this date.
+    pub fn since(self, earlier: Date) -> i64 {
+        self.0 - earlier.0
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = self.ymd();
+        write!(f, "{year:04}-{month:02}-{day:02}")
+    }
+}
+
+impl FromStr for Date {
+    type Err = color_eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.trim().splitn(3, '-').map(str::parse::<i64>);
+        let (Some(Ok(year)), Some(Ok(month)), Some(Ok(day))) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(eyre!("“{s}” is not a date like 2026-03-01"));
+        };
+        Self::from_ymd(year, month as u32, day as u32).ok_or_else(|| eyre!("no such date as “{s}”"))
+    }
+}
+
+impl TryFrom<String> for Date {
+    type Error = color_eyre::Report;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<Date> for String {
+    fn from(date: Date) -> Self {
+        date.to_string()
+    }
+}
+
+/// A plan being followed: the day it was started on, and which of its days
+/// have been read.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Progress {
+    pub plan: String,
+    pub start: Date,
+    /// Indexes of the days read.
+    #[serde(default)]
+    pub done: BTreeSet<usize>,
+}
+
+impl Progress {
+    /// Index of the day due on `today`, or `None` before the start; past the
+    /// end, the last day.
+    pub fn due(&self, today: Date, days: usize) -> Option<usize> {
+        let due = usize::try_from(today.since(self.start)).ok()?;
+        Some(due.min(days.saturating_sub(1)))
+    }
+
+    /// Days due before `today` that have not been read; past the end of the
+    /// plan, every day not read.
+    pub fn missed(&self, today: Date, days: usize) -> Vec<usize> {
+        let due = match self.due(today, days) {
+            Some(_) if today.since(self.start) >= days as i64 => days,
+            Some(due) => due,
+            None => 0,
+        };
+        (0..due).filter(|d| !self.done.contains(d)).collect()
+    }
+}
+
+/// The built-in and imported plans, and progress through those started.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Plans {
+    pub list: Vec<Plan>,
+    pub progress: Vec<Progress>,
+}
+
+impl Plans {
+    /// The built-in plans, those imported into the `plans` data directory,
+    /// and the saved progress; a plan file that cannot be read is skipped.
+    pub fn load() -> Result<Self> {
+        let mut plans = Self {
+            list: builtin::plans(),
+            progress: store::load(&path())?,
+        };
+        if let Ok(entries) = fs::read_dir(dir()) {
+            let mut paths: Vec<PathBuf> =
+                entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+            paths.sort();
+            for path in paths {
+                if let Err(e) = Plan::read(&path).and_then(|plan| plans.add(plan)) {
+                    warn!("skipping {path:?}: {e:#}");
+                }
+            }
+        }
+        Ok(plans)
+    }
+
+    /// Add a plan, unless there is one of its name already, which would hide
+    /// it.
+    fn add(&mut self, plan: Plan) -> Result<()> {
+        if self.get(&plan.name).is_some() {
+            return Err(eyre!("there is already a plan named “{}”", plan.name));
+        }
+        self.list.push(plan);
+        Ok(())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        store::save(&path(), &self.progress)
+    }
+
+    /// Copy a plan file into the `plans` data directory, once it reads as a
+    /// plan with a name of its own, so that it is loaded from then on.
+    pub fn import(&mut self, from: &Path) -> Result<&Plan> {
+        let plan = Plan::read(from)?;
+        let dir = dir();
+        let name = from
+            .file_name()
+            .ok_or_else(|| eyre!("{from:?} is not a file"))?;
+        let to = dir.join(name);
+        if to.exists() {
+            return Err(eyre!("{to:?} was imported already"));
+        }
+        self.add(plan)?;
+        fs::create_dir_all(&dir).wrap_err_with(|| format!("cannot create {dir:?}"))?;
+        fs::copy(from, &to).wrap_err_with(|| format!("cannot copy {from:?} to {to:?}"))?;
+        Ok(&self.list[self.list.len() - 1])
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Plan> {
+        self.list.iter().find(|p| p.name == name)
+    }
+
+    pub fn progress(&self, name: &str) -> Option<&Progress> {
+        self.progress.iter().find(|p| p.plan == name)
+    }
+
+    /// Start a plan on `start`, afresh if it was started before.
+    pub fn start(&mut self, name: &str, start: Date) {
+        self.stop(name);
+        self.progress.push(Progress {
+            plan: name.to_owned(),
+            start,
+            done: BTreeSet::new(),
+        });
+    }
+
+    pub fn stop(&mut self, name: &str) {
+        self.progress.retain(|p| p.plan != name);
+    }
+
+    pub fn mark(&mut self, name: &str, day: usize, done: bool) {
+        if let Some(progress) = self.progress.iter_mut().find(|p| p.plan == name) {
+            if done {
+                progress.done.insert(day);
+            } else {
+                progress.done.remove(&day);
+            }
+        }
+    }
+}
+
+fn dir() -> PathBuf {
+    store::path("plans")
+}
+
+fn path() -> PathBuf {
+    store::path("plans.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_date() {
+        let date = |s: &str| s.parse::<Date>().unwrap();
+        assert_eq!(date("1970-01-01"), Date(0));
+        assert_eq!(date("2024-02-29").to_string(), "2024-02-29");
+        assert_eq!(date("2026-03-01").since(date("2026-02-27")), 2);
+        assert_eq!(date("2001-01-01").since(date("2000-01-01")), 366);
+        assert_eq!(date("1969-12-31"), Date(-1));
+        assert!("2025-02-29".parse::<Date>().is_err());
+        assert!("March 1".parse::<Date>().is_err());
+        assert_eq!(
+            serde_json::to_string(&date("2026-10-17")).unwrap(),
+            "\"2026-10-17\""
+        );
+    }
+
+    #[test]
+    fn test_import() {
+        let plan = Plan::from_json(
+            "psalms",
+            r#"{"days": [["Ps 1", "Prov 1"], "Ps 2; Prov 2"]}"#,
+        )
+        .unwrap();
+        assert_eq!(plan.name, "psalms");
+        assert_eq!(plan.days, [["Ps 1", "Prov 1"], ["Ps 2", "Prov 2"]]);
+        let plan = Plan::from_json("x", r#"[["Gen 1"]]"#).unwrap();
+        assert_eq!(plan.days, [["Gen 1"]]);
+
+        let plan = Plan::from_csv(
+            "gospels",
+            "day,reading\n# advent\n1,Matt 1,\"Luke 1:1-4,26-38\"\n\n2,Matt 2\n",
+        )
+        .unwrap();
+        assert_eq!(
+            plan.days,
+            [vec!["Matt 1", "Luke 1:1-4,26-38"], vec!["Matt 2"]]
+        );
+        assert!(plan.check().is_ok());
+        let plan = Plan::from_csv("bad", "Matt 1\nMattt 2\n").unwrap();
+        assert!(plan.check().unwrap_err().to_string().starts_with("day 2:"));
+    }
+
+    #[test]
+    fn test_unique_names() {
+        let mut plans = Plans {
+            list: builtin::plans(),
+            ..Default::default()
+        };
+        let plan = |name: &str| Plan {
+            name: name.into(),
+            days: vec![vec!["Ps 1".into()]],
+        };
+        let e = plans.add(plan("Bible in a year")).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "there is already a plan named “Bible in a year”"
+        );
+        plans.add(plan("Psalms")).unwrap();
+        assert!(plans.add(plan("Psalms")).is_err());
+        assert_eq!(plans.get("Psalms"), plans.list.last());
+    }
+
+    #[test]
+    fn test_progress() {
+        let start: Date = "2026-01-01".parse().unwrap();
+        let day = |n| start.plus(n);
+        let mut plans = Plans::default();
+        plans.start("year", start);
+        plans.mark("year", 0, true);
+        plans.mark("year", 2, true);
+        let progress = plans.progress("year").unwrap();
+        assert_eq!(progress.due(day(-1), 10), None);
+        assert_eq!(progress.due(day(4), 10), Some(4));
+        assert_eq!(progress.missed(day(4), 10), [1, 3]);
+        assert_eq!(progress.due(day(20), 10), Some(9));
+        assert_eq!(progress.missed(day(20), 10), [1, 3, 4, 5, 6, 7, 8, 9]);
+
+        plans.mark("year", 2, false);
+        assert_eq!(
+            plans.progress("year").unwrap().missed(day(4), 10),
+            [1, 2, 3]
+        );
+        plans.stop("year");
+        assert!(plans.progress("year").is_none());
+    }
+}
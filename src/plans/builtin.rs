@@ -0,0 +1,170 @@
+//! The plans that come with the reader: M'Cheyne's calendar as he wrote it,
+//! and others laid out over the chapters of the canon.
+
+use super::Plan;
+use crate::bible::canon::{BOOKS, KJV_VERSES};
+
+/// Books in roughly the order of the events they tell, or of their writing.
+const HISTORICAL: &[&str] = &[
+    "Genesis",
+    "Job",
+    "Exodus",
+    "Leviticus",
+    "Numbers",
+    "Deuteronomy",
+    "Joshua",
+    "Judges",
+    "Ruth",
+    "1 Samuel",
+    "2 Samuel",
+    "Psalms",
+    "1 Kings",
+    "Proverbs",
+    "Ecclesiastes",
+    "Song of Solomon",
+    "2 Kings",
+    "Jonah",
+    "Amos",
+    "Hosea",
+    "Isaiah",
+    "Micah",
+    "Joel",
+    "Nahum",
+    "Zephaniah",
+    "Habakkuk",
+    "Jeremiah",
+    "Lamentations",
+    "Obadiah",
+    "Ezekiel",
+    "Daniel",
+    "1 Chronicles",
+    "2 Chronicles",
+    "Ezra",
+    "Haggai",
+    "Zechariah",
+    "Esther",
+    "Nehemiah",
+    "Malachi",
+    "Matthew",
+    "Mark",
+    "Luke",
+    "John",
+    "Acts",
+    "James",
+    "Galatians",
+    "1 Thessalonians",
+    "2 Thessalonians",
+    "1 Corinthians",
+    "2 Corinthians",
+    "Romans",
+    "Ephesians",
+    "Philippians",
+    "Colossians",
+    "Philemon",
+    "1 Timothy",
+    "Titus",
+    "1 Peter",
+    "2 Timothy",
+    "2 Peter",
+    "Hebrews",
+    "Jude",
+    "1 John",
+    "2 John",
+    "3 John",
+    "Revelation",
+];
+
+pub fn plans() -> Vec<Plan> {
+    let ot = || chapters(BOOKS[..39].iter().map(|b| b.name));
+    let nt = || chapters(BOOKS[39..].iter().map(|b| b.name));
+    vec![
+        Plan {
+            name: "Bible in a year".into(),
+            days: spread(&[[ot(), nt()].concat()], 365),
+        },
+        // Four readings a day, taking in the Old Testament once and the New
+        // Testament and Psalms twice.
+        Plan::from_csv("M'Cheyne", include_str!("mcheyne.csv"))
+            .expect("the M'Cheyne table is a plan"),
+        // Whole books only; the chapters of each are not interleaved.
+        Plan {
+            name: "Books in historical order".into(),
+            days: spread(&[chapters(HISTORICAL.iter().copied())], 365),
+        },
+        Plan {
+            name: "New Testament in 90 days".into(),
+            days: spread(&[nt()], 90),
+        },
+    ]
+}
+
+fn position(book: &str) -> usize {
+    BOOKS
+        .iter()
+        .position(|b| b.name == book)
+        .unwrap_or_default()
+}
+
+/// Every chapter of `books`, in the order given.
+fn chapters<'a>(books: impl Iterator<Item = &'a str>) -> Vec<(&'a str, u16)> {
+    books
+        .flat_map(|book| (1..=KJV_VERSES[position(book)].len() as u16).map(move |c| (book, c)))
+        .collect()
+}
+
+/// Lay each track of chapters out evenly over `days`, a reading of each
+/// track a day; runs of chapters of one book are joined, as `Genesis 1-3`.
+fn spread(tracks: &[Vec<(&str, u16)>], days: usize) -> Vec<Vec<String>> {
+    (0..days)
+        .map(|day| {
+            let mut readings = Vec::new();
+            for track in tracks {
+                let part = &track[day * track.len() / days..(day + 1) * track.len() / days];
+                for run in part.chunk_by(|a, b| a.0 == b.0) {
+                    let (book, first) = run[0];
+                    let last = run[run.len() - 1].1;
+                    readings.push(match first == last {
+                        true => format!("{book} {first}"),
+                        false => format!("{book} {first}-{last}"),
+                    });
+                }
+            }
+            readings
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_plans() {
+        let mut historical = HISTORICAL.to_vec();
+        historical.sort_by_key(|b| position(b));
+        assert_eq!(historical, BOOKS.iter().map(|b| b.name).collect::<Vec<_>>());
+
+        let plans = plans();
+        let year = &plans[0];
+        assert_eq!(year.days.len(), 365);
+        assert_eq!(year.days[0], ["Genesis 1-3"]);
+        assert_eq!(year.days[364], ["Revelation 19-22"]);
+        assert!(plans.iter().all(|p| p.check().is_ok()));
+        assert!(plans.iter().all(|p| p.days.iter().all(|d| !d.is_empty())));
+
+        let mcheyne = &plans[1];
+        assert_eq!(mcheyne.days.len(), 365);
+        assert!(mcheyne.days.iter().all(|d| d.len() == 4));
+        assert_eq!(
+            mcheyne.days[0],
+            ["Genesis 1", "Matthew 1", "Ezra 1", "Acts 1"]
+        );
+        assert_eq!(mcheyne.days[219][2], "Jeremiah 36, 45");
+        assert_eq!(
+            mcheyne.days[364],
+            ["2 Chronicles 36", "Revelation 22", "Malachi 4", "John 21"]
+        );
+    }
+}
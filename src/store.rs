@@ -0,0 +1,101 @@
+//! What the reader saves between sessions, as JSON files in the data
+//! directory.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::{Result, eyre::WrapErr};
+use serde::{Serialize, de::DeserializeOwned};
+use tracing::warn;
+
+use crate::config::get_data_dir;
+
+/// Path of the data file called `name`.
+pub fn path(name: &str) -> PathBuf {
+    get_data_dir().join(name)
+}
+
+/// Read a data file; a missing one reads as the default. One that cannot be
+/// read is moved aside to `<name>.bak`, or `<name>.bak.1` and so on if that
+/// is taken, so that saving the default does not overwrite it, and reads as
+/// the default too.
+pub fn load<T: DeserializeOwned + Default>(path: &Path) -> Result<T> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let parsed = fs::read_to_string(path)
+        .wrap_err_with(|| format!("cannot read {path:?}"))
+        .and_then(|text| {
+            serde_json::from_str(&text).wrap_err_with(|| format!("cannot parse {path:?}"))
+        });
+    match parsed {
+        Ok(value) => Ok(value),
+        Err(e) => {
+            let backup = backup(path);
+            fs::rename(path, &backup)
+                .wrap_err_with(|| format!("cannot move {path:?} aside after: {e:#}"))?;
+            warn!("{e:#}; starting afresh, the old file is kept as {backup:?}");
+            Ok(T::default())
+        }
+    }
+}
+
+/// The first of `<path>.bak`, `<path>.bak.1`, `<path>.bak.2`, … not taken.
+fn backup(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".bak");
+    let first = PathBuf::from(&name);
+    std::iter::once(first.clone())
+        .chain((1..).map(|n| first.with_extension(format!("bak.{n}"))))
+        .find(|candidate| !candidate.exists())
+        .expect("some backup name is free")
+}
+
+pub fn save<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).wrap_err_with(|| format!("cannot create {dir:?}"))?;
+    }
+    let text = serde_json::to_string_pretty(value)?;
+    fs::write(path, text).wrap_err_with(|| format!("cannot write {path:?}"))
+}
+
+/// Seconds since the Unix epoch, for dating what is saved.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_bad_file_is_moved_aside() {
+        let path = std::env::temp_dir().join(format!("logos-store-{}.json", std::process::id()));
+        let backups = [
+            path.with_extension("json.bak"),
+            path.with_extension("json.bak.1"),
+        ];
+        // A second bad file is moved aside next to the first, not over it.
+        let mut loaded = Vec::new();
+        for text in ["[1, 2,", "[3,"] {
+            fs::write(&path, text).unwrap();
+            loaded.push(load::<Vec<u32>>(&path).unwrap());
+        }
+        let kept = backups.each_ref().map(fs::read_to_string);
+        let moved = !path.exists();
+        for backup in &backups {
+            fs::remove_file(backup).unwrap();
+        }
+
+        assert_eq!(loaded, [Vec::<u32>::new(), Vec::new()]);
+        assert!(moved);
+        assert_eq!(kept.map(Result::unwrap), ["[1, 2,", "[3,"]);
+    }
+}
@@ -0,0 +1,179 @@
+use std::path::{Path, PathBuf};
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{bible::reference::VerseRange, store};
+
+/// A topic put on a verse or a range of verses of one chapter, such as
+/// `grace` or `sermon-2026-03`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tag {
+    pub name: String,
+    pub book: String,
+    pub chapter: u16,
+    pub verse: u16,
+    /// Last verse it is on; `verse` itself for a single verse.
+    pub end: u16,
+    /// When it was put on, in seconds since the Unix epoch.
+    #[serde(default)]
+    pub created: u64,
+}
+
+impl VerseRange for Tag {
+    fn range(&self) -> (&str, u16, u16, u16) {
+        (&self.book, self.chapter, self.verse, self.end)
+    }
+}
+
+impl Tag {
+    pub fn new(name: &str, book: String, chapter: u16, verse: u16, end: u16) -> Self {
+        Self {
+            name: normalize(name),
+            book,
+            chapter,
+            verse,
+            end: end.max(verse),
+            created: store::now(),
+        }
+    }
+
+    /// Whether `other` is the same tag on the same verses.
+    fn is_like(&self, other: &Tag) -> bool {
+        (&self.name, self.range()) == (&other.name, other.range())
+    }
+
+    /// How many verses it is on.
+    fn verses(&self) -> usize {
+        usize::from(self.end.saturating_sub(self.verse)) + 1
+    }
+}
+
+/// A tag name as stored: trimmed, lowercase, with dashes for spaces.
+pub fn normalize(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase()
+}
+
+/// Every tag on every verse; the same name on the same verses only once.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tags {
+    pub list: Vec<Tag>,
+}
+
+impl Tags {
+    /// Read the saved tags; none yet is not an error.
+    pub fn load() -> Result<Self> {
+        Self::load_from(&path())
+    }
+
+    fn load_from(path: &Path) -> Result<Self> {
+        store::load(path)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        store::save(&path(), self)
+    }
+
+    pub fn add(&mut self, tag: Tag) {
+        if !tag.name.is_empty() && !self.list.iter().any(|t| t.is_like(&tag)) {
+            self.list.push(tag);
+        }
+    }
+
+    pub fn remove(&mut self, tag: &Tag) {
+        self.list.retain(|t| !t.is_like(tag));
+    }
+
+    /// Tags on `verse`.
+    pub fn on(&self, book: &str, chapter: u16, verse: u16) -> impl Iterator<Item = &Tag> {
+        let book = book.to_owned();
+        self.list
+            .iter()
+            .filter(move |t| t.covers(&book, chapter, verse))
+    }
+
+    /// The tag `name` on exactly these verses.
+    pub fn get(&self, name: &str, book: &str, chapter: u16, verse: u16, end: u16) -> Option<&Tag> {
+        self.list
+            .iter()
+            .find(|t| t.is_like(&Tag::new(name, book.to_owned(), chapter, verse, end)))
+    }
+
+    /// Every tag name, sorted, with how many verses it is on.
+    pub fn names(&self) -> Vec<(String, usize)> {
+        let mut names: Vec<(String, usize)> = Vec::new();
+        for tag in &self.list {
+            match names.iter_mut().find(|(name, _)| *name == tag.name) {
+                Some((_, count)) => *count += tag.verses(),
+                None => names.push((tag.name.clone(), tag.verses())),
+            }
+        }
+        names.sort();
+        names
+    }
+
+    /// The verses tagged `name`, in bible order.
+    pub fn tagged(&self, name: &str) -> Vec<&Tag> {
+        let mut tagged: Vec<&Tag> = self.list.iter().filter(|t| t.name == name).collect();
+        tagged.sort_by(|a, b| a.position().cmp(&b.position()));
+        tagged
+    }
+}
+
+fn path() -> PathBuf {
+    store::path("tags.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_tags() {
+        let tag = |name: &str, book: &str, chapter, verse, end| {
+            Tag::new(name, book.into(), chapter, verse, end)
+        };
+        assert_eq!(normalize("  Sermon 2026 03 "), "sermon-2026-03");
+
+        let mut tags = Tags::default();
+        tags.add(tag("grace", "Romans", 3, 23, 24));
+        tags.add(tag("Covenant", "Genesis", 15, 18, 18));
+        tags.add(tag("grace", "Ephesians", 2, 8, 9));
+        tags.add(tag("grace", "Genesis", 6, 8, 8));
+        tags.add(tag("grace", "Romans", 3, 23, 24));
+        tags.add(tag(" ", "Romans", 3, 23, 24));
+        assert_eq!(
+            tags.names(),
+            [("covenant".to_owned(), 1), ("grace".to_owned(), 5)]
+        );
+        assert_eq!(
+            tags.tagged("grace")
+                .into_iter()
+                .map(Tag::reference)
+                .collect::<Vec<_>>(),
+            ["Genesis 6:8", "Romans 3:23-24", "Ephesians 2:8-9"]
+        );
+        assert_eq!(tags.on("Romans", 3, 24).count(), 1);
+        assert!(tags.get("Grace", "Ephesians", 2, 8, 9).is_some());
+
+        tags.remove(&tag("grace", "Romans", 3, 23, 24));
+        assert_eq!(tags.names()[1], ("grace".to_owned(), 3));
+
+        // An end before the verse, as in a hand-edited file, counts once.
+        let backwards = Tag {
+            end: 2,
+            ..tag("grace", "Acts", 4, 3, 3)
+        };
+        assert_eq!(backwards.verses(), 1);
+
+        let path = std::env::temp_dir().join(format!("logos-tags-{}.json", std::process::id()));
+        store::save(&path, &tags).unwrap();
+        assert_eq!(Tags::load_from(&path).unwrap(), tags);
+        std::fs::remove_file(&path).unwrap();
+    }
+}